@@ -0,0 +1,133 @@
+//! External command execution
+//!
+//! Docker (and other host tools) are invoked through [`CommandExecutor`] so
+//! callers can be tested against `MockExecutor` (test builds only) without
+//! touching the system.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::process::Output;
+use tokio::process::Command as AsyncCommand;
+
+#[cfg(test)]
+use std::collections::VecDeque;
+#[cfg(test)]
+use std::os::unix::process::ExitStatusExt;
+#[cfg(test)]
+use std::process::ExitStatus;
+#[cfg(test)]
+use std::sync::{Arc, Mutex};
+
+/// Future returned by [`CommandExecutor::run`]
+pub type CommandFuture<'a> = Pin<Box<dyn Future<Output = std::io::Result<Output>> + Send + 'a>>;
+
+/// Runs a program to completion and captures its output
+pub trait CommandExecutor: Send + Sync {
+    fn run<'a>(&'a self, program: &'a str, args: &'a [String]) -> CommandFuture<'a>;
+}
+
+/// Runs commands on the host
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemExecutor;
+
+impl CommandExecutor for SystemExecutor {
+    fn run<'a>(&'a self, program: &'a str, args: &'a [String]) -> CommandFuture<'a> {
+        Box::pin(AsyncCommand::new(program).args(args).output())
+    }
+}
+
+/// Records commands instead of running them
+///
+/// Replies with queued outputs in order, then with empty successful output.
+/// Clones share the same recorded calls and reply queue.
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct MockExecutor {
+    calls: Arc<Mutex<Vec<String>>>,
+    replies: Arc<Mutex<VecDeque<std::io::Result<Output>>>>,
+}
+
+#[cfg(test)]
+impl MockExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue the output for the next unanswered command
+    pub fn push_reply(&self, success: bool, stdout: &str, stderr: &str) {
+        self.replies.lock().unwrap().push_back(Ok(Output {
+            status: ExitStatus::from_raw(if success { 0 } else { 1 << 8 }),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: stderr.as_bytes().to_vec(),
+        }));
+    }
+
+    /// Queue a spawn failure (e.g. `NotFound` for a missing program)
+    pub fn push_error(&self, kind: std::io::ErrorKind) {
+        self.replies.lock().unwrap().push_back(Err(kind.into()));
+    }
+
+    /// Commands run so far, each as `program arg1 arg2 ...`
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl CommandExecutor for MockExecutor {
+    fn run<'a>(&'a self, program: &'a str, args: &'a [String]) -> CommandFuture<'a> {
+        let mut call = vec![program.to_string()];
+        call.extend(args.iter().cloned());
+        self.calls.lock().unwrap().push(call.join(" "));
+
+        let reply = self.replies.lock().unwrap().pop_front().unwrap_or_else(|| {
+            Ok(Output {
+                status: ExitStatus::from_raw(0),
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+            })
+        });
+        Box::pin(async move { reply })
+    }
+}
+
+/// Convert string slices into owned command arguments
+pub fn to_args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_executor_replies_in_order() {
+        let mock = MockExecutor::new();
+        mock.push_reply(true, "abc\n", "");
+        mock.push_reply(false, "", "boom");
+
+        let first = mock.run("docker", &to_args(&["ps"])).await.unwrap();
+        assert!(first.status.success());
+        assert_eq!(first.stdout, b"abc\n");
+
+        let second = mock.run("docker", &to_args(&["rm", "x"])).await.unwrap();
+        assert!(!second.status.success());
+        assert_eq!(second.stderr, b"boom");
+
+        let third = mock.clone().run("true", &[]).await.unwrap();
+        assert!(third.status.success());
+
+        mock.push_error(std::io::ErrorKind::NotFound);
+        let err = mock.run("podman", &[]).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+
+        assert_eq!(mock.calls(), ["docker ps", "docker rm x", "true", "podman"]);
+    }
+
+    #[tokio::test]
+    async fn test_system_executor() {
+        let output = SystemExecutor.run("echo", &to_args(&["hello"])).await.unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "hello");
+    }
+}
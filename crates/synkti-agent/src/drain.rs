@@ -7,8 +7,9 @@
 //!
 //! This module manages the drain phase of stateless failover.
 
-use crate::error::Result;
-use crate::vllm::VllmClient;
+use crate::error::{AgentError as OrchestratorError, Result};
+use crate::proxy::DrainGate;
+use crate::vllm::{RequestCount, VllmClient};
 
 // Note: ELB integration (LoadBalancerManager) is in synkti-providers (private).
 // The public agent handles local drain only. Fleet coordinates ELB operations.
@@ -22,6 +23,19 @@ pub const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 115;
 /// Minimum time to wait before checking drain status (avoid busy polling)
 const POLL_INTERVAL_MS: u64 = 500;
 
+/// How far ahead of its deadline `drain_until` gives up, covering the last
+/// poll overshooting
+pub const DEADLINE_SAFETY_MARGIN: Duration = Duration::from_secs(1);
+
+/// Consecutive polls with vLLM unreachable after which it is assumed gone
+pub const DEFAULT_MAX_PROBE_FAILURES: u32 = 10;
+
+/// Spawn + health-check time assumed before any failover has been observed
+pub const DEFAULT_ESTIMATED_SPAWN_SECS: f64 = 60.0;
+
+/// Number of recent failovers averaged by `SpawnTimeEstimator`
+const SPAWN_ESTIMATE_WINDOW: usize = 10;
+
 /// Status of a drain operation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DrainStatus {
@@ -31,6 +45,12 @@ pub enum DrainStatus {
     Drained,
     /// Timeout reached, force stop required
     TimedOut,
+    /// Deadline reached with requests still in flight; abandoned to leave
+    /// time for the replacement (see `DrainManager::drain_until`)
+    PartiallyDrained,
+    /// vLLM stopped answering both metrics and health checks; assumed crashed
+    /// or already stopped, with the state of in-flight requests unknown
+    Completed,
     /// Error during drain
     Failed,
 }
@@ -44,6 +64,94 @@ pub struct DrainResult {
     pub drain_time_secs: f64,
     /// Instance ID that was drained
     pub instance_id: String,
+    /// Requests still queued when a deadline-bound drain gave up, if known
+    #[serde(default)]
+    pub remaining_requests: Option<u32>,
+}
+
+/// Requests queued on the vLLM server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueDepth {
+    /// Requests currently being generated
+    pub running: u32,
+    /// Requests waiting to be scheduled
+    pub waiting: u32,
+}
+
+impl QueueDepth {
+    /// Running plus waiting requests
+    pub fn total(&self) -> u32 {
+        self.running + self.waiting
+    }
+}
+
+/// Drain progress reported on each poll by `drain_with_progress`
+#[derive(Debug, Clone)]
+pub struct DrainProgress {
+    /// Time since draining started
+    pub elapsed: Duration,
+    /// Drain time left before a force stop
+    pub remaining_budget: Duration,
+    /// Current queue depth (if metrics are available)
+    pub queue: Option<QueueDepth>,
+    /// Smoothed completion rate (requests/second), once measurable
+    pub throughput_rps: Option<f64>,
+    /// Predicted time until the queue is empty, once measurable
+    pub predicted_time_to_idle: Option<Duration>,
+}
+
+impl DrainProgress {
+    /// Whether the drain is predicted to overrun its budget
+    pub fn will_overrun(&self) -> bool {
+        self.predicted_time_to_idle
+            .is_some_and(|predicted| predicted > self.remaining_budget)
+    }
+}
+
+/// Smoothed request completion rate across drain polls
+///
+/// Completions are counted from vLLM's `request_success_total` counter when it
+/// is exported, otherwise inferred from the queue shrinking. Once a rate is
+/// established, polls where nothing finished pull it toward zero, so a stalled
+/// queue doesn't keep an optimistic time-to-idle estimate.
+#[derive(Debug, Default)]
+struct CompletionRate {
+    /// Previous poll: time, completion counter (if exported) and queue depth
+    last: Option<(Instant, Option<f64>, u32)>,
+    rps: Option<f64>,
+}
+
+impl CompletionRate {
+    /// Record a poll; returns the smoothed rate, once something has completed
+    fn observe(&mut self, now: Instant, completed_total: Option<f64>, queued: u32) -> Option<f64> {
+        if let Some((last_time, last_completed, last_queued)) = self.last {
+            let elapsed = now.duration_since(last_time).as_secs_f64();
+            let completed = match (completed_total, last_completed) {
+                (Some(total), Some(last)) => (total - last).max(0.0),
+                _ => last_queued.saturating_sub(queued) as f64,
+            };
+            if elapsed > 0.0 && (completed > 0.0 || self.rps.is_some()) {
+                let rate = completed / elapsed;
+                // Smooth out bursty completions
+                self.rps = Some(match self.rps {
+                    Some(previous) => 0.5 * previous + 0.5 * rate,
+                    None => rate,
+                });
+            }
+        }
+        self.last = Some((now, completed_total, queued));
+        self.rps
+    }
+}
+
+/// Result of a single in-flight check
+enum InflightStatus {
+    /// No running or waiting requests
+    Idle,
+    /// Requests remain (counts unknown if vLLM doesn't export them)
+    Busy(Option<QueueDepth>),
+    /// Neither metrics nor health checks answered
+    Unreachable,
 }
 
 /// Configuration for load balancer integration
@@ -55,6 +163,70 @@ pub struct ElbConfig {
     pub port: Option<i32>,
 }
 
+/// Rolling average of replacement spawn + health-check time
+///
+/// Failover drains the old node while a replacement comes up, so the drain
+/// budget has to leave room for the spawn. This tracks how long recent spawns
+/// actually took. Serializes to JSON so the history survives agent restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpawnTimeEstimator {
+    /// Recent spawn durations in seconds (oldest first)
+    samples: std::collections::VecDeque<f64>,
+}
+
+impl SpawnTimeEstimator {
+    /// Create an estimator with no history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the history saved by `save`, or start empty if there is none yet
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new()),
+            Err(e) => {
+                return Err(OrchestratorError::Config(format!(
+                    "Failed to read {}: {}",
+                    path.display(),
+                    e
+                )));
+            }
+        };
+        serde_json::from_str(&contents).map_err(|e| {
+            OrchestratorError::Config(format!("Invalid spawn history {}: {}", path.display(), e))
+        })
+    }
+
+    /// Save the history to `path`, creating its directory if needed
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Record how long a replacement took to become healthy
+    pub fn record(&mut self, spawn_time: Duration) {
+        if self.samples.len() == SPAWN_ESTIMATE_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(spawn_time.as_secs_f64());
+    }
+
+    /// Average of recent spawns, or `DEFAULT_ESTIMATED_SPAWN_SECS` with no history
+    pub fn estimated_spawn_secs(&self) -> f64 {
+        if self.samples.is_empty() {
+            DEFAULT_ESTIMATED_SPAWN_SECS
+        } else {
+            self.samples.iter().sum::<f64>() / self.samples.len() as f64
+        }
+    }
+}
+
 /// Manages graceful request draining during failover
 ///
 /// The drain manager coordinates with the vLLM API to:
@@ -66,6 +238,12 @@ pub struct DrainManager {
     drain_timeout: Duration,
     /// Optional load balancer configuration
     elb_config: Option<ElbConfig>,
+    /// Gate shared with the drain proxy
+    gate: DrainGate,
+    /// Close the gate when draining starts
+    reject_new: bool,
+    /// Consecutive unreachable polls tolerated before concluding the drain
+    max_probe_failures: u32,
 }
 
 impl DrainManager {
@@ -79,6 +257,9 @@ impl DrainManager {
         Self {
             drain_timeout,
             elb_config: None,
+            gate: DrainGate::new(),
+            reject_new: false,
+            max_probe_failures: DEFAULT_MAX_PROBE_FAILURES,
         }
     }
 
@@ -88,31 +269,84 @@ impl DrainManager {
         self
     }
 
+    /// Reject new requests at the drain proxy once draining starts
+    ///
+    /// Only has an effect when vLLM is fronted by `proxy::serve_proxy` using
+    /// this manager's `drain_gate()`. See the `proxy` module for how this
+    /// complements ELB deregistration.
+    pub fn with_reject_new(mut self, reject_new: bool) -> Self {
+        self.reject_new = reject_new;
+        self
+    }
+
+    /// Conclude the drain after this many consecutive polls where neither
+    /// metrics nor the health check answer
+    ///
+    /// A server that stays unreachable is most likely crashed or already
+    /// stopped; waiting out the full timeout for it gains nothing. A server
+    /// that still answers health checks is treated as busy however long its
+    /// metrics stay broken.
+    pub fn with_max_probe_failures(mut self, max_failures: u32) -> Self {
+        self.max_probe_failures = max_failures;
+        self
+    }
+
+    /// Share an existing gate (e.g. one already handed to the proxy)
+    pub fn with_drain_gate(mut self, gate: DrainGate) -> Self {
+        self.gate = gate;
+        self
+    }
+
+    /// Gate to pass to the drain proxy
+    pub fn drain_gate(&self) -> DrainGate {
+        self.gate.clone()
+    }
+
     /// Signal that an instance is entering drain mode
     ///
     /// When ELB is configured, this will:
     /// 1. Deregister the instance from the target group
     /// 2. Start connection draining (LB stops new connections)
     ///
-    /// Without ELB, this just logs the intent.
+    /// With `with_reject_new(true)`, the drain proxy starts answering new
+    /// requests with 503. Otherwise this just logs the intent.
     pub async fn set_draining(&self, instance_id: &str) -> Result<()> {
         info!(
             instance_id = %instance_id,
             "Marking instance as draining - no new requests will be accepted"
         );
 
+        if self.reject_new {
+            self.gate.start_draining();
+        }
+
         Ok(())
     }
 
     // Note: ELB drain functions (set_draining_with_elb, drain_with_elb) are in synkti-fleet.
     // The public agent handles local container draining only.
 
+    /// Predict how long it takes to empty the queue at the given completion rate
+    ///
+    /// Returns zero for an empty queue and `Duration::MAX` when requests remain
+    /// but nothing is completing.
+    pub fn estimate_time_to_idle(running: u32, waiting: u32, throughput_rps: f64) -> Duration {
+        let queued = running + waiting;
+        if queued == 0 {
+            return Duration::ZERO;
+        }
+        if throughput_rps <= 0.0 {
+            return Duration::MAX;
+        }
+        Duration::from_secs_f64(queued as f64 / throughput_rps)
+    }
+
     /// Wait for in-flight requests to complete
     ///
     /// Polls the vLLM server until:
     /// - All requests complete (success)
     /// - Timeout is reached (force stop needed)
-    /// - Server becomes unhealthy (error)
+    /// - Server stops answering for `max_probe_failures` polls (assumed gone)
     ///
     /// # Arguments
     /// - `vllm_client`: Client for querying vLLM status
@@ -121,9 +355,29 @@ impl DrainManager {
         &self,
         vllm_client: &VllmClient,
         timeout: Duration,
+    ) -> Result<DrainStatus> {
+        self.wait_for_inflight_with_progress(vllm_client, timeout, |_| {})
+            .await
+    }
+
+    /// Like `wait_for_inflight`, reporting progress on every poll
+    ///
+    /// The completion rate comes from vLLM's finished-request counter (see
+    /// `VllmClient::get_completed_requests`), falling back to how fast the queue
+    /// shrinks between polls. When the predicted time to idle exceeds the remaining budget a
+    /// warning is logged, so the orchestrator can start the replacement early.
+    pub async fn wait_for_inflight_with_progress(
+        &self,
+        vllm_client: &VllmClient,
+        timeout: Duration,
+        mut on_progress: impl FnMut(&DrainProgress),
     ) -> Result<DrainStatus> {
         let start = Instant::now();
         let poll_interval = Duration::from_millis(POLL_INTERVAL_MS);
+        let mut completion_rate = CompletionRate::default();
+        let mut throughput_rps: Option<f64> = None;
+        let mut overrun_warned = false;
+        let mut probe_failures: u32 = 0;
 
         info!(
             timeout_secs = timeout.as_secs(),
@@ -142,15 +396,63 @@ impl DrainManager {
             }
 
             // Check if server is still processing
-            match self.check_inflight_status(vllm_client).await {
-                Ok(true) => {
+            let status = self.check_inflight_status(vllm_client).await;
+            if matches!(status, Ok(InflightStatus::Unreachable)) {
+                probe_failures += 1;
+            } else {
+                probe_failures = 0;
+            }
+
+            match status {
+                Ok(InflightStatus::Unreachable) => {
+                    if probe_failures >= self.max_probe_failures {
+                        warn!(
+                            failures = probe_failures,
+                            "vLLM persistently unreachable, assuming it is gone"
+                        );
+                        return Ok(DrainStatus::Completed);
+                    }
+                    debug!(failures = probe_failures, "vLLM unreachable");
+                }
+                Ok(InflightStatus::Busy(queue)) => {
                     // Still has in-flight requests, continue waiting
                     debug!(
                         elapsed_secs = elapsed.as_secs_f64(),
                         "Still draining, in-flight requests remain"
                     );
+
+                    if let Some(depth) = queue {
+                        let completed_total =
+                            vllm_client.get_completed_requests().await.ok().flatten();
+                        throughput_rps =
+                            completion_rate.observe(Instant::now(), completed_total, depth.total());
+                    }
+
+                    let progress = DrainProgress {
+                        elapsed,
+                        remaining_budget: timeout - elapsed,
+                        queue,
+                        throughput_rps,
+                        predicted_time_to_idle: queue.zip(throughput_rps).map(|(depth, rps)| {
+                            Self::estimate_time_to_idle(depth.running, depth.waiting, rps)
+                        }),
+                    };
+
+                    if progress.will_overrun() && !overrun_warned {
+                        warn!(
+                            predicted_secs = progress
+                                .predicted_time_to_idle
+                                .map(|d| d.as_secs_f64())
+                                .unwrap_or_default(),
+                            remaining_secs = progress.remaining_budget.as_secs_f64(),
+                            "Drain predicted to overrun its budget, start the replacement early"
+                        );
+                        overrun_warned = true;
+                    }
+
+                    on_progress(&progress);
                 }
-                Ok(false) => {
+                Ok(InflightStatus::Idle) => {
                     // All requests drained
                     info!(
                         elapsed_secs = elapsed.as_secs_f64(),
@@ -178,49 +480,43 @@ impl DrainManager {
     /// if requests are still being processed.
     ///
     /// Returns:
-    /// - `Ok(true)` if requests are still in-flight
-    /// - `Ok(false)` if server is idle
-    /// - `Err` if metrics query fails
-    async fn check_inflight_status(&self, vllm_client: &VllmClient) -> Result<bool> {
+    /// - `Ok(Busy(..))` if requests are still in-flight, or metrics fail but
+    ///   the server is healthy
+    /// - `Ok(Idle)` if server is idle
+    /// - `Ok(Unreachable)` if metrics fail and the health check fails too
+    async fn check_inflight_status(&self, vllm_client: &VllmClient) -> Result<InflightStatus> {
         // Try to get precise request counts from vLLM metrics
-        match vllm_client.is_idle().await {
-            Ok(true) => {
-                // Server is idle - no in-flight requests
-                debug!("vLLM server is idle (no running or waiting requests)");
-                Ok(false)
-            }
-            Ok(false) => {
-                // Still has in-flight or waiting requests
-                match (
-                    vllm_client.get_running_requests().await,
-                    vllm_client.get_waiting_requests().await,
-                ) {
-                    (Ok(running), Ok(waiting)) => {
-                        debug!(
-                            running = running,
-                            waiting = waiting,
-                            "vLLM still processing requests"
-                        );
-                        Ok(true)
-                    }
-                    _ => {
-                        // Couldn't get detailed counts, but we know it's not idle
-                        Ok(true)
-                    }
+        match vllm_client.get_request_counts().await {
+            Ok((RequestCount::Known(running), RequestCount::Known(waiting))) => {
+                if running == 0 && waiting == 0 {
+                    debug!("vLLM server is idle (no running or waiting requests)");
+                    Ok(InflightStatus::Idle)
+                } else {
+                    debug!(
+                        running = running,
+                        waiting = waiting,
+                        "vLLM still processing requests"
+                    );
+                    Ok(InflightStatus::Busy(Some(QueueDepth { running, waiting })))
                 }
             }
+            Ok(_) => {
+                // No source for one of the counts - assume requests are still in flight
+                debug!("In-flight request count unknown, assuming busy");
+                Ok(InflightStatus::Busy(None))
+            }
             Err(e) => {
                 // Can't reach server or metrics - check health as fallback
                 debug!(error = %e, "Metrics query failed, falling back to health check");
                 match vllm_client.health_check().await {
                     Ok(true) => {
                         // Server is healthy but metrics unavailable
-                        // Conservative: assume might still have requests
-                        Ok(true)
+                        // Conservative: treat as busy until the timeout
+                        Ok(InflightStatus::Busy(None))
                     }
                     Ok(false) | Err(_) => {
-                        // Server is unhealthy or unreachable - safe to stop
-                        Ok(false)
+                        // Server is unhealthy or unreachable - may already be gone
+                        Ok(InflightStatus::Unreachable)
                     }
                 }
             }
@@ -239,6 +535,19 @@ impl DrainManager {
         &self,
         instance_id: &str,
         vllm_client: &VllmClient,
+    ) -> Result<DrainResult> {
+        self.drain_with_progress(instance_id, vllm_client, |_| {})
+            .await
+    }
+
+    /// Perform the full drain sequence, reporting progress on every poll
+    ///
+    /// See `wait_for_inflight_with_progress` for what is reported.
+    pub async fn drain_with_progress(
+        &self,
+        instance_id: &str,
+        vllm_client: &VllmClient,
+        on_progress: impl FnMut(&DrainProgress),
     ) -> Result<DrainResult> {
         let start = Instant::now();
 
@@ -247,7 +556,7 @@ impl DrainManager {
 
         // Step 2: Wait for in-flight requests
         let status = self
-            .wait_for_inflight(vllm_client, self.drain_timeout)
+            .wait_for_inflight_with_progress(vllm_client, self.drain_timeout, on_progress)
             .await?;
 
         let drain_time = start.elapsed();
@@ -256,6 +565,7 @@ impl DrainManager {
             status,
             drain_time_secs: drain_time.as_secs_f64(),
             instance_id: instance_id.to_string(),
+            remaining_requests: None,
         };
 
         info!(
@@ -267,11 +577,78 @@ impl DrainManager {
         Ok(result)
     }
 
+    /// Drain until `deadline`, then give up rather than overrun it
+    ///
+    /// For when the time left must be split with spawning a replacement: the
+    /// drain returns `PartiallyDrained` `DEADLINE_SAFETY_MARGIN` before the
+    /// deadline, with the requests still queued (when known), instead of
+    /// holding on until the instance disappears. The configured drain timeout
+    /// is not used.
+    pub async fn drain_until(
+        &self,
+        instance_id: &str,
+        vllm_client: &VllmClient,
+        deadline: Instant,
+    ) -> Result<DrainResult> {
+        let start = Instant::now();
+        let budget = deadline
+            .saturating_duration_since(start)
+            .saturating_sub(DEADLINE_SAFETY_MARGIN);
+
+        self.set_draining(instance_id).await?;
+
+        let mut remaining = None;
+        let status = self
+            .wait_for_inflight_with_progress(vllm_client, budget, |progress| {
+                remaining = progress.queue.map(|queue| queue.total());
+            })
+            .await?;
+
+        let (status, remaining_requests) = match status {
+            DrainStatus::TimedOut => {
+                warn!(
+                    remaining = ?remaining,
+                    "Drain deadline reached, abandoning remaining requests"
+                );
+                (DrainStatus::PartiallyDrained, remaining)
+            }
+            status => (status, None),
+        };
+
+        let result = DrainResult {
+            status,
+            drain_time_secs: start.elapsed().as_secs_f64(),
+            instance_id: instance_id.to_string(),
+            remaining_requests,
+        };
+        info!(
+            status = ?result.status,
+            drain_time_secs = result.drain_time_secs,
+            "Drain sequence completed"
+        );
+        Ok(result)
+    }
+
 
     /// Get the configured drain timeout
     pub fn drain_timeout(&self) -> Duration {
         self.drain_timeout
     }
+
+    /// Drain time available for a notice, leaving room to spawn a replacement
+    ///
+    /// `max(0, seconds_until_action - estimated_spawn_secs - safety_margin)`,
+    /// capped by the configured drain timeout.
+    pub fn effective_budget(
+        &self,
+        seconds_until_action: u64,
+        estimated_spawn_secs: f64,
+        safety_margin: Duration,
+    ) -> Duration {
+        let available =
+            seconds_until_action as f64 - estimated_spawn_secs - safety_margin.as_secs_f64();
+        Duration::from_secs_f64(available.max(0.0)).min(self.drain_timeout)
+    }
 }
 
 impl Default for DrainManager {
@@ -299,6 +676,211 @@ mod tests {
         assert_eq!(manager.drain_timeout().as_secs(), 60);
     }
 
+    #[test]
+    fn test_effective_budget_shrinks_for_short_notice() {
+        let manager = DrainManager::new();
+        let margin = Duration::from_secs(5);
+
+        // Full notice: capped by the configured timeout
+        assert_eq!(manager.effective_budget(300, 20.0, margin).as_secs(), 115);
+        assert_eq!(manager.effective_budget(120, 20.0, margin).as_secs(), 95);
+
+        // 30s notice leaves only 5s to drain
+        assert_eq!(manager.effective_budget(30, 20.0, margin).as_secs(), 5);
+        assert_eq!(manager.effective_budget(10, 20.0, margin), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_estimate_time_to_idle() {
+        assert_eq!(DrainManager::estimate_time_to_idle(0, 0, 0.0), Duration::ZERO);
+        assert_eq!(DrainManager::estimate_time_to_idle(0, 0, 5.0), Duration::ZERO);
+        assert_eq!(
+            DrainManager::estimate_time_to_idle(4, 6, 2.0),
+            Duration::from_secs(5)
+        );
+        assert_eq!(DrainManager::estimate_time_to_idle(3, 0, 0.0), Duration::MAX);
+    }
+
+    #[test]
+    fn test_completion_rate_decays_when_stalled() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut rate = CompletionRate::default();
+
+        // Nothing measurable until something completes
+        assert_eq!(rate.observe(at(0), Some(10.0), 8), None);
+        assert_eq!(rate.observe(at(1), Some(10.0), 8), None);
+
+        // Counted from the counter, not the queue (new arrivals kept it at 8)
+        assert_eq!(rate.observe(at(2), Some(14.0), 8), Some(4.0));
+
+        // Stalled: the rate halves on each poll
+        assert_eq!(rate.observe(at(3), Some(14.0), 8), Some(2.0));
+        assert_eq!(rate.observe(at(4), Some(14.0), 8), Some(1.0));
+
+        // Without the counter, the shrinking queue is used
+        let mut rate = CompletionRate::default();
+        rate.observe(at(0), None, 10);
+        assert_eq!(rate.observe(at(2), None, 4), Some(3.0));
+    }
+
+    #[test]
+    fn test_progress_overrun() {
+        let mut progress = DrainProgress {
+            elapsed: Duration::from_secs(10),
+            remaining_budget: Duration::from_secs(20),
+            queue: Some(QueueDepth { running: 8, waiting: 32 }),
+            throughput_rps: Some(1.0),
+            predicted_time_to_idle: Some(Duration::from_secs(40)),
+        };
+        assert!(progress.will_overrun());
+
+        progress.predicted_time_to_idle = Some(Duration::from_secs(15));
+        assert!(!progress.will_overrun());
+
+        progress.predicted_time_to_idle = None;
+        assert!(!progress.will_overrun());
+    }
+
+    #[test]
+    fn test_spawn_time_estimator_rolling_average() {
+        let mut estimator = SpawnTimeEstimator::new();
+        assert_eq!(estimator.estimated_spawn_secs(), DEFAULT_ESTIMATED_SPAWN_SECS);
+
+        estimator.record(Duration::from_secs(30));
+        estimator.record(Duration::from_secs(50));
+        assert_eq!(estimator.estimated_spawn_secs(), 40.0);
+
+        for _ in 0..SPAWN_ESTIMATE_WINDOW {
+            estimator.record(Duration::from_secs(20));
+        }
+        assert_eq!(estimator.estimated_spawn_secs(), 20.0);
+    }
+
+    #[test]
+    fn test_spawn_time_estimator_persists() {
+        let dir = std::env::temp_dir().join(format!("synkti-spawn-times-{}", std::process::id()));
+        let path = dir.join("spawn-times.json");
+
+        let mut estimator = SpawnTimeEstimator::load(&path).unwrap();
+        assert_eq!(estimator.estimated_spawn_secs(), DEFAULT_ESTIMATED_SPAWN_SECS);
+
+        estimator.record(Duration::from_secs(30));
+        estimator.save(&path).unwrap();
+        assert_eq!(SpawnTimeEstimator::load(&path).unwrap().estimated_spawn_secs(), 30.0);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(SpawnTimeEstimator::load(&path).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_set_draining_closes_gate_only_when_rejecting() {
+        let manager = DrainManager::new();
+        manager.set_draining("i-test").await.unwrap();
+        assert!(!manager.drain_gate().is_draining());
+
+        let gate = DrainGate::new();
+        let manager = DrainManager::new()
+            .with_reject_new(true)
+            .with_drain_gate(gate.clone());
+        manager.set_draining("i-test").await.unwrap();
+        assert!(gate.is_draining());
+    }
+
+    #[tokio::test]
+    async fn test_drain_assumes_gone_after_repeated_probe_failures() {
+        // Nothing listening: metrics and health checks both error
+        let manager = DrainManager::with_timeout(Duration::from_secs(30)).with_max_probe_failures(3);
+        let result = manager
+            .drain("i-test", &VllmClient::new("http://127.0.0.1:1"))
+            .await
+            .unwrap();
+
+        assert_eq!(result.status, DrainStatus::Completed);
+        assert!(result.drain_time_secs < 5.0, "took {}s", result.drain_time_secs);
+    }
+
+    #[tokio::test]
+    async fn test_drain_waits_out_healthy_server_with_broken_metrics() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Healthy server whose metrics endpoint keeps failing
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/metrics"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let manager = DrainManager::with_timeout(Duration::from_secs(2)).with_max_probe_failures(1);
+        let result = manager
+            .drain("i-test", &VllmClient::new(server.uri()))
+            .await
+            .unwrap();
+
+        assert_eq!(result.status, DrainStatus::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn test_drain_waits_when_waiting_metric_missing() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Nothing running, but the queue depth isn't exported
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/metrics"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string("vllm:num_requests_running 0\n"),
+            )
+            .mount(&server)
+            .await;
+
+        let manager = DrainManager::with_timeout(Duration::from_secs(2));
+        let result = manager
+            .drain("i-test", &VllmClient::new(server.uri()))
+            .await
+            .unwrap();
+
+        assert_eq!(result.status, DrainStatus::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn test_drain_until_gives_up_at_deadline() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Requests that never finish
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/metrics"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "vllm:num_requests_running 3.0\nvllm:num_requests_waiting 2.0\n",
+            ))
+            .mount(&server)
+            .await;
+
+        // The configured timeout would wait far longer than the deadline
+        let manager = DrainManager::with_timeout(Duration::from_secs(60));
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let result = manager
+            .drain_until("i-test", &VllmClient::new(server.uri()), deadline)
+            .await
+            .unwrap();
+
+        assert!(Instant::now() < deadline);
+        assert_eq!(result.status, DrainStatus::PartiallyDrained);
+        assert_eq!(result.remaining_requests, Some(5));
+    }
+
     #[test]
     fn test_drain_status_serialization() {
         let status = DrainStatus::Drained;
@@ -312,6 +894,7 @@ mod tests {
             status: DrainStatus::Drained,
             drain_time_secs: 5.5,
             instance_id: "i-1234567890abcdef0".to_string(),
+            remaining_requests: None,
         };
 
         let json = serde_json::to_string(&result).unwrap();
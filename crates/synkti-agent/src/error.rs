@@ -24,6 +24,14 @@ pub enum AgentError {
     #[error("Docker error: {0}")]
     Docker(String),
 
+    #[error("{kind}: {detail}. {}", kind.remediation())]
+    DockerFailed { kind: DockerFailure, detail: String },
+
+    #[error(
+        "Docker is not installed or not on PATH; install Docker Engine (https://docs.docker.com/engine/install/) and make sure the agent can run `docker`"
+    )]
+    DockerNotInstalled,
+
     #[error("Config error: {0}")]
     Config(String),
 
@@ -39,6 +47,226 @@ pub enum AgentError {
     #[error("Health check failed: {0}")]
     HealthCheck(String),
 
+    #[error("AWS request throttled{}", retry_after.map(|d| format!(" (retry after {:?})", d)).unwrap_or_default())]
+    Throttled { retry_after: Option<Duration> },
+
+    #[error("Access denied for AWS action: {action}")]
+    AccessDenied { action: String },
+
+    #[error("Rolling restart failed: {}", failures.iter().map(|(id, e)| format!("{}: {}", id, e)).collect::<Vec<_>>().join("; "))]
+    RollingRestart { failures: Vec<(String, AgentError)> },
+
+    #[error("Unsupported operation: {0}")]
+    Unsupported(String),
+
     #[error("{0}")]
     Other(String),
 }
+
+/// Common Docker failures, recognised from a command's stderr and exit code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DockerFailure {
+    /// The image couldn't be pulled (missing tag, auth, rate limit)
+    ImagePull,
+    /// The container was killed for running out of memory (exit 137)
+    OutOfMemory,
+    /// NVIDIA runtime, driver or CUDA problem
+    Cuda,
+    /// The host port is already bound
+    PortInUse,
+}
+
+impl DockerFailure {
+    /// Classify a failed Docker command; `None` if nothing matches
+    pub fn classify(stderr: &str, exit_code: Option<i32>) -> Option<Self> {
+        let stderr = stderr.to_lowercase();
+        let mentions = |patterns: &[&str]| patterns.iter().any(|p| stderr.contains(p));
+
+        // Checked first: image names like `nvidia/cuda:...` would otherwise
+        // look like GPU problems
+        if mentions(&[
+            "pull access denied",
+            "manifest unknown",
+            "manifest for ",
+            "failed to resolve reference",
+            "toomanyrequests",
+            "error pulling image",
+        ]) {
+            Some(Self::ImagePull)
+        } else if mentions(&[
+            // Checked before OOM: "CUDA out of memory" is a GPU problem
+            "cuda error",
+            "cuda out of memory",
+            "cuda driver",
+            "cuda runtime",
+            "no cuda gpus",
+            "nvidia-container-cli",
+            "could not select device driver",
+            "nvidia driver",
+        ]) {
+            Some(Self::Cuda)
+        } else if exit_code == Some(137) || mentions(&["oomkilled", "out of memory"]) {
+            Some(Self::OutOfMemory)
+        } else if mentions(&["port is already allocated", "address already in use"]) {
+            Some(Self::PortInUse)
+        } else {
+            None
+        }
+    }
+
+    /// What the operator should do about it
+    pub fn remediation(&self) -> &'static str {
+        match self {
+            Self::ImagePull => {
+                "Check the image name and tag, registry credentials (docker login) and Docker Hub rate limits"
+            }
+            Self::OutOfMemory => {
+                "Raise the container memory limit or use a larger instance, or reduce max_model_len"
+            }
+            Self::Cuda => {
+                "Check nvidia-smi, the NVIDIA driver version against the image's CUDA version, and that nvidia-container-toolkit is installed"
+            }
+            Self::PortInUse => {
+                "Stop whatever holds the port (docker ps, ss -ltnp) or configure a different port"
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for DockerFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::ImagePull => "Docker image pull failed",
+            Self::OutOfMemory => "Container ran out of memory",
+            Self::Cuda => "GPU runtime error",
+            Self::PortInUse => "Port already in use",
+        })
+    }
+}
+
+impl AgentError {
+    /// Error for a failed Docker command, classified when recognisable
+    pub fn from_docker_output(context: &str, stderr: &str, exit_code: Option<i32>) -> Self {
+        let detail = format!("{}: {}", context, stderr.trim());
+        match DockerFailure::classify(stderr, exit_code) {
+            Some(kind) => Self::DockerFailed { kind, detail },
+            None => Self::Docker(detail),
+        }
+    }
+
+    /// Map an AWS error code to a typed error
+    ///
+    /// `action` is the API operation that failed (e.g. `DescribeInstances`), used
+    /// in access-denied messages. Unrecognised codes fall back to `Other`.
+    pub fn from_aws_code(code: &str, action: &str, message: &str) -> Self {
+        match code {
+            "RequestLimitExceeded" | "Throttling" | "ThrottlingException" => {
+                Self::Throttled { retry_after: None }
+            }
+            "UnauthorizedOperation" | "AccessDenied" | "AccessDeniedException" => {
+                Self::AccessDenied {
+                    action: action.to_string(),
+                }
+            }
+            _ => Self::Other(format!("{} failed ({}): {}", action, code, message)),
+        }
+    }
+
+    /// Whether retrying the failed operation may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Throttled { .. } | Self::Timeout(_) => true,
+            Self::Http(e) => e.is_timeout() || e.is_connect(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_aws_code() {
+        let err = AgentError::from_aws_code("RequestLimitExceeded", "DescribeInstances", "slow down");
+        assert!(matches!(err, AgentError::Throttled { retry_after: None }));
+        assert!(err.is_retryable());
+
+        let err = AgentError::from_aws_code("UnauthorizedOperation", "TerminateInstances", "denied");
+        assert!(matches!(err, AgentError::AccessDenied { ref action } if action == "TerminateInstances"));
+        assert!(!err.is_retryable());
+
+        let err = AgentError::from_aws_code("InvalidInstanceID.NotFound", "DescribeInstances", "gone");
+        assert!(matches!(err, AgentError::Other(_)));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_docker_failure_classification() {
+        let cases = [
+            (
+                "docker: Error response from daemon: pull access denied for vllm/vllm-opnai, repository does not exist",
+                Some(125),
+                Some(DockerFailure::ImagePull),
+            ),
+            (
+                "docker: Error response from daemon: manifest for vllm/vllm-openai:v9 not found: manifest unknown",
+                Some(125),
+                Some(DockerFailure::ImagePull),
+            ),
+            (
+                "docker: Error response from daemon: manifest for nvidia/cuda:12.9.9-base not found",
+                Some(125),
+                Some(DockerFailure::ImagePull),
+            ),
+            ("", Some(137), Some(DockerFailure::OutOfMemory)),
+            (
+                "docker: Error response from daemon: could not select device driver \"\" with capabilities: [[gpu]]",
+                Some(125),
+                Some(DockerFailure::Cuda),
+            ),
+            (
+                "torch.OutOfMemoryError: CUDA out of memory. Tried to allocate 2.00 GiB",
+                Some(1),
+                Some(DockerFailure::Cuda),
+            ),
+            (
+                "RuntimeError: CUDA error: no kernel image is available for execution on the device",
+                Some(1),
+                Some(DockerFailure::Cuda),
+            ),
+            // Mentioning CUDA alone isn't a GPU failure
+            ("Unpacking nvidia/cuda layers... invalid reference format", Some(125), None),
+            (
+                "Bind for 0.0.0.0:8000 failed: port is already allocated",
+                Some(125),
+                Some(DockerFailure::PortInUse),
+            ),
+            ("docker: invalid reference format", Some(125), None),
+        ];
+        for (stderr, code, expected) in cases {
+            assert_eq!(DockerFailure::classify(stderr, code), expected, "{}", stderr);
+        }
+
+        let err = AgentError::from_docker_output(
+            "vLLM container failed to start",
+            "Bind for 0.0.0.0:8000 failed: port is already allocated\n",
+            Some(125),
+        );
+        assert!(matches!(err, AgentError::DockerFailed { kind: DockerFailure::PortInUse, .. }));
+        assert!(err.to_string().starts_with("Port already in use: vLLM container failed to start: Bind"));
+        assert!(err.to_string().ends_with("or configure a different port"));
+
+        let err = AgentError::from_docker_output("docker stop failed", "boom", Some(1));
+        assert!(matches!(err, AgentError::Docker(_)));
+    }
+
+    #[test]
+    fn test_throttled_display() {
+        let err = AgentError::Throttled {
+            retry_after: Some(Duration::from_secs(2)),
+        };
+        assert_eq!(err.to_string(), "AWS request throttled (retry after 2s)");
+        assert!(AgentError::Timeout(Duration::from_secs(1)).is_retryable());
+    }
+}
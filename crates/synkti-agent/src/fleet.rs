@@ -0,0 +1,142 @@
+//! Fleet API client
+//!
+//! The agent reports lifecycle transitions (draining, drained, suspended,
+//! resumed) to the fleet
+//! API so the control plane can stop routing to the node and schedule a
+//! replacement. Reporting is best effort: a node must still drain and stop
+//! when the fleet API is unreachable.
+
+use crate::drain::DrainResult;
+use crate::error::{AgentError as OrchestratorError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::debug;
+
+/// Lifecycle state reported to the fleet API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeState {
+    /// Spot notice received, draining in-flight requests
+    Draining,
+    /// Drain finished and the container was stopped
+    Drained,
+    /// Drain finished ahead of a spot stop/hibernate; the node will come back
+    Suspended,
+    /// Node came back from a stop/hibernate and is serving again
+    Resumed,
+}
+
+/// Status update posted to `POST {fleet_api}/nodes/{instance_id}/status`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeStatusUpdate {
+    /// Instance reporting the update
+    pub instance_id: String,
+    /// New lifecycle state
+    pub state: NodeState,
+    /// Drain outcome (set once draining finishes)
+    pub drain: Option<DrainResult>,
+    /// When the update was produced
+    pub timestamp: DateTime<Utc>,
+}
+
+impl NodeStatusUpdate {
+    /// Create an update stamped with the current time
+    pub fn new(instance_id: impl Into<String>, state: NodeState) -> Self {
+        Self {
+            instance_id: instance_id.into(),
+            state,
+            drain: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Attach a drain result
+    pub fn with_drain(mut self, drain: DrainResult) -> Self {
+        self.drain = Some(drain);
+        self
+    }
+}
+
+/// Client for the fleet API
+pub struct FleetClient {
+    /// Base URL for the fleet API
+    base_url: String,
+
+    /// HTTP client
+    client: reqwest::Client,
+}
+
+impl FleetClient {
+    /// Create a new fleet API client
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap(),
+        }
+    }
+
+    /// Post a status update for this node
+    pub async fn report_status(&self, update: &NodeStatusUpdate) -> Result<()> {
+        let url = format!("{}/nodes/{}/status", self.base_url, update.instance_id);
+        debug!(url = %url, state = ?update.state, "Reporting node status to fleet API");
+
+        let response = self.client.post(&url).json(update).send().await?;
+
+        if !response.status().is_success() {
+            return Err(OrchestratorError::Other(format!(
+                "Fleet API rejected status update: status {}",
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Get base URL
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_report_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/nodes/i-abc/status"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = FleetClient::new(format!("{}/", server.uri()));
+        let update = NodeStatusUpdate::new("i-abc", NodeState::Draining);
+        client.report_status(&update).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let body: NodeStatusUpdate = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body.state, NodeState::Draining);
+        assert!(body.drain.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_report_status_rejected() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let client = FleetClient::new(server.uri());
+        let update = NodeStatusUpdate::new("i-abc", NodeState::Drained);
+        assert!(client.report_status(&update).await.is_err());
+    }
+}
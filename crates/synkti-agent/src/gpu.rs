@@ -0,0 +1,137 @@
+//! GPU utilization probe
+//!
+//! Reads per-GPU memory and utilization from `nvidia-smi`. Request counts
+//! alone are a poor load signal when request cost varies widely; this gives
+//! schedulers the actual memory in use.
+
+use crate::command::{CommandExecutor, to_args};
+use crate::error::{AgentError as OrchestratorError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Fields requested from `nvidia-smi --query-gpu`, in output order
+pub const NVIDIA_SMI_QUERY: &str = "index,memory.used,utilization.gpu";
+
+/// Utilization of a single GPU
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GpuUtil {
+    /// GPU index as reported by nvidia-smi
+    pub index: u32,
+    /// Memory in use (MB), if the GPU reports it
+    pub mem_used_mb: Option<f64>,
+    /// Compute utilization over the last sample period (0-100), if the GPU
+    /// reports it
+    pub util_percent: Option<f64>,
+}
+
+/// Parse `nvidia-smi --query-gpu=index,memory.used,utilization.gpu --format=csv`
+///
+/// Accepts output with or without the header row and unit suffixes
+/// (`noheader`/`nounits`). Placeholders such as `[N/A]` (MIG devices, some
+/// datacenter SKUs) or `[Not Supported]` are read as `None`.
+pub fn parse_gpu_utilization(csv: &str) -> Result<Vec<GpuUtil>> {
+    let unparseable = |field: &str, line: &str| {
+        OrchestratorError::Other(format!("Unparseable nvidia-smi value '{}' in line '{}'", field, line))
+    };
+    let parse_field = |field: &str, line: &str| -> Result<Option<f64>> {
+        if field.starts_with('[') {
+            return Ok(None);
+        }
+        let value = field.split_whitespace().next().unwrap_or_default();
+        value.parse::<f64>().map(Some).map_err(|_| unparseable(field, line))
+    };
+
+    csv.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("index"))
+        .map(|line| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [index, mem_used, util] = fields[..] else {
+                return Err(OrchestratorError::Other(format!(
+                    "Expected 3 nvidia-smi fields, got {}: '{}'",
+                    fields.len(),
+                    line
+                )));
+            };
+
+            Ok(GpuUtil {
+                index: index.parse().map_err(|_| unparseable(index, line))?,
+                mem_used_mb: parse_field(mem_used, line)?,
+                util_percent: parse_field(util, line)?,
+            })
+        })
+        .collect()
+}
+
+/// Query utilization of all local GPUs via `nvidia-smi`, run by `executor`
+pub async fn query_gpu_utilization(executor: &dyn CommandExecutor) -> Result<Vec<GpuUtil>> {
+    let query = format!("--query-gpu={}", NVIDIA_SMI_QUERY);
+    let output = executor
+        .run("nvidia-smi", &to_args(&[&query, "--format=csv,noheader,nounits"]))
+        .await?;
+
+    if !output.status.success() {
+        return Err(OrchestratorError::Other(format!(
+            "nvidia-smi failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    parse_gpu_utilization(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gpu_utilization() {
+        let csv = "\
+index, memory.used [MiB], utilization.gpu [%]
+0, 21034 MiB, 87 %
+1, 512 MiB, 0 %
+";
+        let gpus = parse_gpu_utilization(csv).unwrap();
+        assert_eq!(
+            gpus,
+            vec![
+                GpuUtil { index: 0, mem_used_mb: Some(21034.0), util_percent: Some(87.0) },
+                GpuUtil { index: 1, mem_used_mb: Some(512.0), util_percent: Some(0.0) },
+            ]
+        );
+
+        // noheader,nounits form
+        let gpus = parse_gpu_utilization("0, 1024.5, 50\n").unwrap();
+        assert_eq!(gpus[0].mem_used_mb, Some(1024.5));
+
+        // MIG and some datacenter SKUs don't report every field
+        let gpus = parse_gpu_utilization("0, [N/A], [Not Supported]").unwrap();
+        assert_eq!(gpus, vec![GpuUtil { index: 0, mem_used_mb: None, util_percent: None }]);
+
+        assert!(parse_gpu_utilization("0.5, 1024, 50").is_err());
+        assert!(parse_gpu_utilization("0, MiB, 50").is_err());
+        assert!(parse_gpu_utilization("0, 1024").is_err());
+        assert!(parse_gpu_utilization("").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_query_gpu_utilization() {
+        use crate::command::MockExecutor;
+
+        let smi = MockExecutor::new();
+        smi.push_reply(true, "0, 1024, 50\n", "");
+        smi.push_reply(false, "", "NVIDIA-SMI has failed because it couldn't communicate with the NVIDIA driver");
+
+        let gpus = query_gpu_utilization(&smi).await.unwrap();
+        assert_eq!(
+            gpus,
+            vec![GpuUtil { index: 0, mem_used_mb: Some(1024.0), util_percent: Some(50.0) }]
+        );
+        let err = query_gpu_utilization(&smi).await.unwrap_err();
+        assert!(err.to_string().contains("couldn't communicate"));
+
+        assert_eq!(
+            smi.calls()[0],
+            "nvidia-smi --query-gpu=index,memory.used,utilization.gpu --format=csv,noheader,nounits"
+        );
+    }
+}
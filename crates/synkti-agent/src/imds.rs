@@ -0,0 +1,318 @@
+//! EC2 instance metadata (IMDS)
+//!
+//! `ImdsClient` is the single entry point for metadata reads: identity and
+//! placement of the node (the region and availability zone needed for
+//! region-correct AWS clients and AZ-aware placement, and the instance
+//! lifecycle) and pending spot actions for the monitor.
+//!
+//! Requests use IMDSv2: a session token is fetched with `PUT /latest/api/token`,
+//! cached until shortly before its TTL runs out, and sent with every metadata
+//! read. Inside a container the token response is one network hop further
+//! away, so the instance's metadata hop limit must be at least 2
+//! (`aws ec2 modify-instance-metadata-options --http-put-response-hop-limit 2`).
+
+use crate::error::{AgentError as OrchestratorError, Result};
+use crate::monitor::SpotInterruptionNotice;
+use chrono::Utc;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// EC2 instance metadata endpoint base URL
+pub const IMDS_BASE: &str = "http://169.254.169.254";
+
+/// IMDSv2 token endpoint
+const TOKEN_PATH: &str = "/latest/api/token";
+
+/// Default IMDSv2 token lifetime (6 hours, the IMDS maximum)
+pub const DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(21600);
+
+/// Tokens are refreshed this long before they expire
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(30);
+
+/// Per-request timeout; IMDS is link-local and answers in milliseconds
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Identity and placement of the instance the agent runs on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstanceInfo {
+    pub instance_id: String,
+    pub instance_type: String,
+    /// e.g. `us-east-1`
+    pub region: String,
+    /// e.g. `us-east-1a`
+    pub availability_zone: String,
+    /// `spot` or `on-demand`
+    pub lifecycle: String,
+}
+
+impl InstanceInfo {
+    /// Whether this is a spot instance
+    pub fn is_spot(&self) -> bool {
+        self.lifecycle == "spot"
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    value: String,
+    refresh_at: Instant,
+}
+
+/// IMDSv2 client with a cached session token
+///
+/// Cloning is cheap and clones share the token cache.
+#[derive(Debug, Clone)]
+pub struct ImdsClient {
+    base_url: String,
+    client: reqwest::Client,
+    token_ttl: Duration,
+    token: Arc<Mutex<Option<CachedToken>>>,
+}
+
+impl ImdsClient {
+    /// Create a client for the metadata service at `base_url`
+    ///
+    /// Use `ImdsClient::default()` on EC2; tests point this at a mock server.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap(),
+            token_ttl: DEFAULT_TOKEN_TTL,
+            token: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Request tokens with a different lifetime (whole seconds, at least 1)
+    pub fn with_token_ttl(mut self, ttl: Duration) -> Self {
+        self.token_ttl = ttl.max(Duration::from_secs(1));
+        self
+    }
+
+    /// Get the instance ID
+    pub async fn instance_id(&self) -> Result<String> {
+        self.get_required("instance-id").await
+    }
+
+    /// Get the instance type (e.g. `g5.xlarge`)
+    pub async fn instance_type(&self) -> Result<String> {
+        self.get_required("instance-type").await
+    }
+
+    /// Get the region (e.g. `us-east-1`)
+    pub async fn region(&self) -> Result<String> {
+        self.get_required("placement/region").await
+    }
+
+    /// Get the availability zone (e.g. `us-east-1a`)
+    pub async fn az(&self) -> Result<String> {
+        self.get_required("placement/availability-zone").await
+    }
+
+    /// Get the instance lifecycle (`spot` or `on-demand`)
+    pub async fn lifecycle(&self) -> Result<String> {
+        self.get_required("instance-life-cycle").await
+    }
+
+    /// Get the pending spot interruption, if any
+    ///
+    /// IMDS answers 404 until AWS schedules an action.
+    pub async fn spot_action(&self) -> Result<Option<SpotInterruptionNotice>> {
+        match self.get("spot/instance-action").await? {
+            Some(body) => SpotInterruptionNotice::from_instance_action(&body, Utc::now()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetch the instance's identity and placement
+    pub async fn instance_info(&self) -> Result<InstanceInfo> {
+        Ok(InstanceInfo {
+            instance_id: self.instance_id().await?,
+            instance_type: self.instance_type().await?,
+            region: self.region().await?,
+            availability_zone: self.az().await?,
+            lifecycle: self.lifecycle().await?,
+        })
+    }
+
+    async fn get_required(&self, path: &str) -> Result<String> {
+        self.get(path)
+            .await?
+            .ok_or_else(|| OrchestratorError::Other(format!("IMDS {} not found", path)))
+    }
+
+    /// Read a metadata path; `Ok(None)` on 404
+    ///
+    /// A 401 means the cached token was revoked or expired early; it is
+    /// dropped and the read retried once with a fresh token.
+    async fn get(&self, path: &str) -> Result<Option<String>> {
+        let url = format!("{}/latest/meta-data/{}", self.base_url, path);
+
+        for attempt in 0..2 {
+            let token = self.token().await?;
+            let response = self
+                .client
+                .get(&url)
+                .header("X-aws-ec2-metadata-token", token)
+                .send()
+                .await?;
+
+            match response.status() {
+                reqwest::StatusCode::NOT_FOUND => return Ok(None),
+                reqwest::StatusCode::UNAUTHORIZED if attempt == 0 => {
+                    debug!("IMDS token rejected, refreshing");
+                    *self.token.lock().unwrap() = None;
+                }
+                status if status.is_success() => {
+                    return Ok(Some(response.text().await?.trim().to_string()));
+                }
+                status => {
+                    return Err(OrchestratorError::Other(format!(
+                        "IMDS {} returned {}",
+                        path, status
+                    )));
+                }
+            }
+        }
+
+        Err(OrchestratorError::Other(format!(
+            "IMDS {} rejected a freshly issued token",
+            path
+        )))
+    }
+
+    /// Return the cached token, fetching a new one if it is missing or due
+    async fn token(&self) -> Result<String> {
+        if let Some(cached) = self.token.lock().unwrap().as_ref()
+            && Instant::now() < cached.refresh_at
+        {
+            return Ok(cached.value.clone());
+        }
+
+        let issued_at = Instant::now();
+        let value = self
+            .client
+            .put(format!("{}{}", self.base_url, TOKEN_PATH))
+            .header(
+                "X-aws-ec2-metadata-token-ttl-seconds",
+                self.token_ttl.as_secs().to_string(),
+            )
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        *self.token.lock().unwrap() = Some(CachedToken {
+            value: value.clone(),
+            refresh_at: issued_at + self.token_ttl.saturating_sub(TOKEN_REFRESH_MARGIN),
+        });
+        Ok(value)
+    }
+}
+
+impl Default for ImdsClient {
+    fn default() -> Self {
+        Self::new(IMDS_BASE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::SpotAction;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn mock_token(imds: &MockServer, token: &str, times: u64) {
+        Mock::given(method("PUT"))
+            .and(path(TOKEN_PATH))
+            .respond_with(ResponseTemplate::new(200).set_body_string(token))
+            .up_to_n_times(times)
+            .expect(times)
+            .mount(imds)
+            .await;
+    }
+
+    async fn mock_field(imds: &MockServer, field: &str, token: &str, status: u16, body: &str) {
+        Mock::given(method("GET"))
+            .and(path(format!("/latest/meta-data/{}", field)))
+            .and(header("X-aws-ec2-metadata-token", token))
+            .respond_with(ResponseTemplate::new(status).set_body_string(body))
+            .mount(imds)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_instance_info() {
+        let imds = MockServer::start().await;
+        mock_token(&imds, "tok", 1).await;
+        for (field, value) in [
+            ("instance-id", "i-0abc"),
+            ("instance-type", "g5.xlarge"),
+            ("placement/region", "us-west-2"),
+            ("placement/availability-zone", "us-west-2b"),
+            ("instance-life-cycle", "spot"),
+        ] {
+            mock_field(&imds, field, "tok", 200, value).await;
+        }
+
+        // Five reads, one token
+        let info = ImdsClient::new(imds.uri()).instance_info().await.unwrap();
+        assert_eq!(
+            info,
+            InstanceInfo {
+                instance_id: "i-0abc".to_string(),
+                instance_type: "g5.xlarge".to_string(),
+                region: "us-west-2".to_string(),
+                availability_zone: "us-west-2b".to_string(),
+                lifecycle: "spot".to_string(),
+            }
+        );
+        assert!(info.is_spot());
+    }
+
+    #[tokio::test]
+    async fn test_token_refreshed_when_due() {
+        let imds = MockServer::start().await;
+        mock_token(&imds, "tok", 2).await;
+        mock_field(&imds, "instance-id", "tok", 200, "i-0abc").await;
+
+        // A 1s TTL is inside the refresh margin, so every read re-fetches
+        let client = ImdsClient::new(imds.uri()).with_token_ttl(Duration::from_secs(1));
+        assert_eq!(client.instance_id().await.unwrap(), "i-0abc");
+        assert_eq!(client.instance_id().await.unwrap(), "i-0abc");
+    }
+
+    #[tokio::test]
+    async fn test_rejected_token_refreshed_once() {
+        let imds = MockServer::start().await;
+        mock_token(&imds, "stale", 1).await;
+        mock_token(&imds, "fresh", 1).await;
+        mock_field(&imds, "placement/region", "stale", 401, "").await;
+        mock_field(&imds, "placement/region", "fresh", 200, "eu-west-1").await;
+
+        let client = ImdsClient::new(imds.uri());
+        assert_eq!(client.region().await.unwrap(), "eu-west-1");
+    }
+
+    #[tokio::test]
+    async fn test_spot_action() {
+        let imds = MockServer::start().await;
+        mock_token(&imds, "tok", 1).await;
+
+        let client = ImdsClient::new(imds.uri());
+        assert!(client.spot_action().await.unwrap().is_none());
+
+        let time = (Utc::now() + chrono::Duration::seconds(120)).to_rfc3339();
+        let body = format!(r#"{{"action": "stop", "time": "{}"}}"#, time);
+        mock_field(&imds, "spot/instance-action", "tok", 200, &body).await;
+
+        let notice = client.spot_action().await.unwrap().unwrap();
+        assert_eq!(notice.action, SpotAction::Stop);
+    }
+}
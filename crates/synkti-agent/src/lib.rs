@@ -0,0 +1,29 @@
+//! Synkti Agent - Spot instance node library
+//!
+//! Building blocks used by the `synkti-agent` binary and by fleet components:
+//! - Spot interruption monitoring and instance metadata (monitor.rs, imds.rs)
+//! - Container lifecycle, crash supervision and model swaps (vllm.rs, supervisor.rs, swap.rs)
+//! - Batched rolling restarts across nodes (rolling.rs)
+//! - Graceful shutdown (drain.rs, proxy.rs, shutdown.rs)
+//! - Fleet API reporting (fleet.rs)
+//! - Agent HTTP server, log capture and metrics (server.rs, logs.rs, metrics.rs)
+//! - GPU utilization probe (gpu.rs)
+//! - Injectable command execution (command.rs)
+//! - Error types (error.rs)
+
+pub mod error;
+pub mod monitor;
+pub mod imds;
+pub mod vllm;
+pub mod supervisor;
+pub mod swap;
+pub mod rolling;
+pub mod drain;
+pub mod proxy;
+pub mod fleet;
+pub mod shutdown;
+pub mod logs;
+pub mod server;
+pub mod metrics;
+pub mod gpu;
+pub mod command;
@@ -0,0 +1,170 @@
+//! In-memory log capture
+//!
+//! Keeps the last K log records in a ring buffer so operators can read recent
+//! agent logs from the `/logs` endpoint without SSH access, even when the
+//! fleet log pipeline is down.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Default number of log records kept
+pub const DEFAULT_LOG_CAPACITY: usize = 500;
+
+/// A captured log record
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogRecord {
+    /// When the event was recorded
+    pub timestamp: DateTime<Utc>,
+    /// Log level (`INFO`, `WARN`, ...)
+    pub level: String,
+    /// Module path of the event
+    pub target: String,
+    /// Message followed by any structured fields (`key=value`)
+    pub message: String,
+}
+
+/// Bounded ring buffer of recent log records
+///
+/// Cloning is cheap and clones share the same buffer.
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    records: Arc<Mutex<VecDeque<LogRecord>>>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    /// Create a buffer holding at most `capacity` records
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Append a record, evicting the oldest when full
+    pub fn push(&self, record: LogRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut records = self.records.lock().unwrap();
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    /// Retained records, oldest first
+    pub fn recent_logs(&self) -> Vec<LogRecord> {
+        self.records.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Maximum number of records kept
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Tracing layer that feeds this buffer
+    pub fn layer(&self) -> LogCaptureLayer {
+        LogCaptureLayer {
+            buffer: self.clone(),
+        }
+    }
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOG_CAPACITY)
+    }
+}
+
+/// Tracing layer capturing events into a `LogBuffer`
+pub struct LogCaptureLayer {
+    buffer: LogBuffer,
+}
+
+impl<S: Subscriber> Layer<S> for LogCaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        self.buffer.push(LogRecord {
+            timestamp: Utc::now(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: visitor.finish(),
+        });
+    }
+}
+
+/// Collects the `message` field and formats the rest as `key=value`
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    fn finish(self) -> String {
+        if self.fields.is_empty() {
+            self.message
+        } else if self.message.is_empty() {
+            self.fields.trim_start().to_string()
+        } else {
+            format!("{}{}", self.message, self.fields)
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_buffer_keeps_last_k_records_in_order() {
+        let buffer = LogBuffer::new(3);
+        let subscriber = tracing_subscriber::registry().with(buffer.layer());
+
+        tracing::subscriber::with_default(subscriber, || {
+            for i in 0..5 {
+                tracing::info!(iteration = i, "log line {}", i);
+            }
+            tracing::warn!("last");
+        });
+
+        let logs = buffer.recent_logs();
+        let messages: Vec<&str> = logs.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec!["log line 3 iteration=3", "log line 4 iteration=4", "last"]
+        );
+        assert_eq!(logs[0].level, "INFO");
+        assert_eq!(logs[2].level, "WARN");
+        assert!(logs[0].timestamp <= logs[2].timestamp);
+    }
+}
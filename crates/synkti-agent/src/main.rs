@@ -8,15 +8,22 @@
 //! Binary: synkti-agent
 
 use clap::Parser;
-use futures::StreamExt;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-mod error;
-mod monitor;
-mod vllm;
-mod drain;
+use synkti_agent::command::SystemExecutor;
+use synkti_agent::drain::{DEFAULT_MAX_PROBE_FAILURES, SpawnTimeEstimator};
+use synkti_agent::fleet::FleetClient;
+use synkti_agent::imds::ImdsClient;
+use synkti_agent::logs::{DEFAULT_LOG_CAPACITY, LogBuffer};
+use synkti_agent::metrics::AgentMetrics;
+use synkti_agent::monitor::{self, CloudProvider};
+use synkti_agent::proxy::{self, DrainGate};
+use synkti_agent::server::{self, ServerState};
+use synkti_agent::shutdown::{STOP_BUFFER_SECS, TerminationHandler};
+use synkti_agent::vllm::VllmClient;
 
 /// Synkti Agent - Node binary for spot instances
 #[derive(Parser)]
@@ -34,44 +41,159 @@ struct Cli {
     /// Health check port
     #[arg(long, default_value = "8080")]
     port: u16,
+
+    /// Instance ID reported to the fleet API
+    #[arg(long, env = "SYNKTI_INSTANCE_ID", default_value = "local")]
+    instance_id: String,
+
+    /// Local vLLM API URL
+    #[arg(long, default_value = "http://localhost:8000")]
+    vllm_url: String,
+
+    /// vLLM container to stop after draining
+    #[arg(long)]
+    container_name: Option<String>,
+
+    /// Front vLLM with a proxy on this port that rejects new requests while draining
+    #[arg(long)]
+    proxy_port: Option<u16>,
+
+    /// Marker written when a spot stop/hibernate suspends the node; its
+    /// presence at startup means the node is resuming
+    #[arg(long, default_value = "/var/lib/synkti/suspended")]
+    resume_marker: String,
+
+    /// Recent resume times, kept across runs; the drain budget leaves this
+    /// much room for a replacement to come up
+    #[arg(long, default_value = "/var/lib/synkti/spawn-times.json")]
+    spawn_history: String,
+
+    /// Cloud provider (aws, gcp, azure); detected from DMI data if omitted
+    #[arg(long)]
+    provider: Option<CloudProvider>,
+
+    /// Maximum drain time in seconds; must be shorter than the provider's
+    /// grace period (defaults to the grace period minus a stop buffer)
+    #[arg(long)]
+    drain_timeout: Option<u64>,
+
+    /// Consecutive polls with vLLM unreachable after which a drain assumes it is gone
+    #[arg(long, default_value_t = DEFAULT_MAX_PROBE_FAILURES)]
+    max_probe_failures: u32,
+
+    /// Number of recent log records served at /logs
+    #[arg(long, default_value_t = DEFAULT_LOG_CAPACITY)]
+    log_buffer: usize,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
     // Initialize logging
+    let log_buffer = LogBuffer::new(cli.log_buffer);
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "synkti_agent=info,info".into()),
         )
         .with(tracing_subscriber::fmt::layer())
+        .with(log_buffer.layer())
         .init();
 
-    let cli = Cli::parse();
-
     info!("========================================");
     info!("Synkti Agent starting");
     info!("Version: {}", env!("CARGO_PKG_VERSION"));
     info!("Monitor interval: {}s", cli.monitor_interval);
     info!("========================================");
 
+    let provider = cli.provider.unwrap_or_else(CloudProvider::detect);
+    let grace_period = provider.grace_period();
+    let drain_timeout = Duration::from_secs(
+        cli.drain_timeout
+            .unwrap_or(grace_period.as_secs().saturating_sub(STOP_BUFFER_SECS)),
+    );
+    monitor::validate_drain_timeout(drain_timeout, grace_period)?;
+    info!(
+        "Provider: {} ({}s grace period, {}s drain timeout)",
+        provider,
+        grace_period.as_secs(),
+        drain_timeout.as_secs()
+    );
+
+    // Start the agent HTTP server
+    let started_at = Instant::now();
+    let metrics = AgentMetrics::new()?;
+    // Closed when a drain starts: fails /readyz and, with --proxy-port, rejects new requests
+    let drain_gate = DrainGate::new();
+    let instance_type = ImdsClient::default().instance_type().await.ok();
+    let state = ServerState {
+        logs: log_buffer,
+        metrics: metrics.clone(),
+        vllm: VllmClient::new(&cli.vllm_url),
+        instance_id: cli.instance_id.clone(),
+        instance_type,
+        started_at,
+        drain_gate: Some(drain_gate.clone()),
+        executor: Arc::new(SystemExecutor),
+    };
+    let port = cli.port;
+    tokio::spawn(async move {
+        if let Err(e) = server::serve(port, state).await {
+            warn!("Agent HTTP server stopped: {}", e);
+        }
+    });
+
     // Start spot monitoring
     let monitor = monitor::SpotMonitor::with_interval(Duration::from_secs(cli.monitor_interval));
-    let mut stream = monitor.monitor_stream();
 
-    info!("Spot monitoring active");
+    let spawn_estimator = SpawnTimeEstimator::load(&cli.spawn_history).unwrap_or_else(|e| {
+        warn!("Ignoring spawn history: {}", e);
+        SpawnTimeEstimator::new()
+    });
+    info!("Estimated spawn time: {:.0}s", spawn_estimator.estimated_spawn_secs());
 
-    while let Some(notice) = stream.next().await {
-        match notice.action {
-            monitor::SpotAction::Terminate => {
-                warn!(
-                    "SPOT TERMINATION NOTICE: {} seconds until termination",
-                    notice.seconds_until_action
-                );
-                // TODO: Notify fleet API, initiate drain
+    let mut handler = TerminationHandler::new(&cli.instance_id, VllmClient::new(&cli.vllm_url))
+        .with_metrics(metrics)
+        .with_resume_marker(&cli.resume_marker)
+        .with_spawn_estimator(spawn_estimator)
+        .with_spawn_history(&cli.spawn_history)
+        .with_drain_timeout(drain_timeout)
+        .with_max_probe_failures(cli.max_probe_failures)
+        .with_drain_gate(drain_gate.clone());
+    if let Some(ref fleet_api) = cli.fleet_api {
+        handler = handler.with_fleet(FleetClient::new(fleet_api));
+    }
+    if let Some(ref name) = cli.container_name {
+        handler = handler.with_container_name(name);
+    }
+    if let Some(proxy_port) = cli.proxy_port {
+        let upstream = cli.vllm_url.clone();
+        tokio::spawn(async move {
+            if let Err(e) = proxy::serve_proxy(proxy_port, upstream, drain_gate).await {
+                warn!("Drain proxy stopped: {}", e);
             }
-            _ => {}
-        }
+        });
+    }
+
+    // A notice still pending on this boot means the suspension hasn't happened yet
+    let pending = monitor.check_notice().await.unwrap_or_else(|e| {
+        warn!("Failed to check for a pending spot notice: {}", e);
+        None
+    });
+    if let Err(e) = handler.resume_if_suspended(pending.as_ref()).await {
+        warn!("Failed to resume after spot stop/hibernate: {}", e);
+    }
+
+    info!("Spot monitoring active");
+
+    // Keep serving 503s from /readyz and the drain proxy until the instance
+    // goes away; after a stop/hibernate the next agent start resumes from the marker
+    if let Some(notice) =
+        monitor::dispatch_notices(monitor.monitor_stream(), &mut handler).await
+    {
+        info!(action = ?notice.action, "Notice handled; waiting for the instance to go down");
+        std::future::pending::<()>().await;
     }
 
     Ok(())
@@ -0,0 +1,108 @@
+//! Prometheus metrics for the agent
+//!
+//! vLLM exposes its own serving metrics; these cover the failover side that
+//! SREs alert on: failover outcomes and drain durations. Rendered in the text exposition format at
+//! `/metrics` on the agent HTTP server.
+
+use crate::drain::{DrainResult, DrainStatus};
+use crate::error::{AgentError as OrchestratorError, Result};
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounterVec, Opts, Registry, TextEncoder};
+
+/// Drain duration buckets (seconds), up to the 120s spot grace period
+const DRAIN_BUCKETS: &[f64] = &[1.0, 5.0, 10.0, 30.0, 60.0, 90.0, 115.0, 120.0];
+
+/// Agent metrics, registered in a dedicated registry
+///
+/// Cloning is cheap and clones update the same metrics.
+#[derive(Clone)]
+pub struct AgentMetrics {
+    registry: Registry,
+    failovers: IntCounterVec,
+    drain_duration: Histogram,
+}
+
+impl AgentMetrics {
+    /// Create and register all agent metrics
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+
+        let failovers = IntCounterVec::new(
+            Opts::new("synkti_failovers_total", "Failovers handled, by result"),
+            &["result"],
+        )
+        .map_err(metrics_error)?;
+        let drain_duration = Histogram::with_opts(
+            HistogramOpts::new(
+                "synkti_drain_duration_seconds",
+                "Time spent draining in-flight requests",
+            )
+            .buckets(DRAIN_BUCKETS.to_vec()),
+        )
+        .map_err(metrics_error)?;
+
+        registry.register(Box::new(failovers.clone())).map_err(metrics_error)?;
+        registry.register(Box::new(drain_duration.clone())).map_err(metrics_error)?;
+
+        Ok(Self {
+            registry,
+            failovers,
+            drain_duration,
+        })
+    }
+
+    /// Record the outcome of a failover
+    pub fn record_failover(&self, success: bool) {
+        let result = if success { "success" } else { "failure" };
+        self.failovers.with_label_values(&[result]).inc();
+    }
+
+    /// Record a completed drain
+    pub fn record_drain(&self, drain: &DrainResult) {
+        self.drain_duration.observe(drain.drain_time_secs);
+    }
+
+    /// Record a full failover from its drain result (timeouts count as failures)
+    pub fn record_drain_failover(&self, drain: &DrainResult) {
+        self.record_drain(drain);
+        self.record_failover(drain.status == DrainStatus::Drained);
+    }
+
+    /// Render all metrics in the Prometheus text format
+    pub fn render(&self) -> Result<String> {
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buf)
+            .map_err(metrics_error)?;
+        String::from_utf8(buf).map_err(|e| OrchestratorError::Other(e.to_string()))
+    }
+}
+
+fn metrics_error(e: prometheus::Error) -> OrchestratorError {
+    OrchestratorError::Other(format!("Metrics error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failover_counter_increments() {
+        let metrics = AgentMetrics::new().unwrap();
+        let drain = DrainResult {
+            status: DrainStatus::Drained,
+            drain_time_secs: 4.0,
+            instance_id: "i-test".to_string(),
+            remaining_requests: None,
+        };
+
+        metrics.record_drain_failover(&drain);
+        metrics.record_drain_failover(&drain);
+        metrics.record_failover(false);
+
+        let text = metrics.render().unwrap();
+        assert!(text.contains("synkti_failovers_total{result=\"success\"} 2"));
+        assert!(text.contains("synkti_failovers_total{result=\"failure\"} 1"));
+        assert!(text.contains("synkti_drain_duration_seconds_count 2"));
+        assert!(text.contains("synkti_drain_duration_seconds_bucket{le=\"5\"} 2"));
+    }
+}
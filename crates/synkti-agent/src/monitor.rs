@@ -1,6 +1,7 @@
 //! Spot instance interruption monitoring
 //!
-//! Polls the EC2 instance metadata endpoint for spot termination notices.
+//! Polls the EC2 instance metadata endpoint (through `imds::ImdsClient`) for
+//! spot termination notices.
 //!
 //! ## Metadata Endpoint
 //!
@@ -22,27 +23,200 @@
 //! ## Grace Period
 //!
 //! AWS provides a 120-second grace period between the notice and actual termination.
-//! This is our window to checkpoint and migrate.
+//! This is our window to drain and migrate. GCP and Azure give about 30 seconds,
+//! so the grace period is taken from the `CloudProvider`, and the drain timeout
+//! must fit inside it (`validate_drain_timeout`).
 
 use crate::error::{AgentError as OrchestratorError, Result};
+use crate::imds::ImdsClient;
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
+use futures::{Stream, StreamExt};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::ops::ControlFlow;
 use std::pin::Pin;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::time::interval;
 use tracing::{debug, info, warn};
 
-/// EC2 instance metadata endpoint base URL
-const METADATA_BASE: &str = "http://169.254.169.254";
-
-/// Spot instance action endpoint
-const SPOT_ACTION_ENDPOINT: &str = "/latest/meta-data/spot/instance-action";
-
 /// AWS standard grace period for spot termination (seconds)
 pub const GRACE_PERIOD_SECONDS: u64 = 120;
 
-/// Spot interruption action type
+/// GCP preemption notice before shutdown (seconds)
+pub const GCP_GRACE_PERIOD_SECONDS: u64 = 30;
+
+/// Azure spot eviction notice (seconds)
+pub const AZURE_GRACE_PERIOD_SECONDS: u64 = 30;
+
+/// DMI file naming the hypervisor vendor, used to detect the provider
+const SYS_VENDOR_PATH: &str = "/sys/class/dmi/id/sys_vendor";
+
+/// Cloud provider hosting the node, which determines the grace period
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloudProvider {
+    Aws,
+    Gcp,
+    Azure,
+}
+
+impl CloudProvider {
+    /// Time between the interruption notice and the hard kill
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(match self {
+            Self::Aws => GRACE_PERIOD_SECONDS,
+            Self::Gcp => GCP_GRACE_PERIOD_SECONDS,
+            Self::Azure => AZURE_GRACE_PERIOD_SECONDS,
+        })
+    }
+
+    /// Identify the provider from the DMI `sys_vendor` string
+    pub fn from_sys_vendor(vendor: &str) -> Option<Self> {
+        let vendor = vendor.trim();
+        if vendor.starts_with("Amazon") {
+            Some(Self::Aws)
+        } else if vendor.starts_with("Google") {
+            Some(Self::Gcp)
+        } else if vendor.starts_with("Microsoft") {
+            Some(Self::Azure)
+        } else {
+            None
+        }
+    }
+
+    /// Detect the provider from DMI data, defaulting to AWS
+    pub fn detect() -> Self {
+        std::fs::read_to_string(SYS_VENDOR_PATH)
+            .ok()
+            .and_then(|vendor| Self::from_sys_vendor(&vendor))
+            .unwrap_or(Self::Aws)
+    }
+
+    /// Lowercase name, as accepted by `--provider`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Aws => "aws",
+            Self::Gcp => "gcp",
+            Self::Azure => "azure",
+        }
+    }
+}
+
+impl std::fmt::Display for CloudProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for CloudProvider {
+    type Err = OrchestratorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "aws" => Ok(Self::Aws),
+            "gcp" => Ok(Self::Gcp),
+            "azure" => Ok(Self::Azure),
+            _ => Err(OrchestratorError::Config(format!(
+                "Unknown cloud provider: {} (expected aws, gcp or azure)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Reject a drain timeout that would run into the provider's hard kill
+pub fn validate_drain_timeout(drain_timeout: Duration, grace_period: Duration) -> Result<()> {
+    if drain_timeout >= grace_period {
+        return Err(OrchestratorError::Config(format!(
+            "Drain timeout ({}s) must be shorter than the {}s interruption grace period",
+            drain_timeout.as_secs(),
+            grace_period.as_secs()
+        )));
+    }
+    Ok(())
+}
+
+/// Spot reclaim risk inferred from recent spot prices
+///
+/// A softer signal than the interruption notice: capacity is usually reclaimed
+/// when the spot price climbs towards the bid, so a rising price lets the
+/// orchestrator pre-warm a replacement before any notice arrives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+/// Price-to-max-price ratios at which risk escalates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskThresholds {
+    /// Ratio at or above which risk is Medium
+    pub medium: f64,
+    /// Ratio at or above which risk is High
+    pub high: f64,
+}
+
+impl RiskThresholds {
+    fn classify(&self, ratio: f64) -> RiskLevel {
+        if ratio >= self.high {
+            RiskLevel::High
+        } else if ratio >= self.medium {
+            RiskLevel::Medium
+        } else {
+            RiskLevel::Low
+        }
+    }
+}
+
+impl Default for RiskThresholds {
+    fn default() -> Self {
+        Self {
+            medium: 0.7,
+            high: 0.9,
+        }
+    }
+}
+
+/// Classify reclaim risk from `prices` (oldest first) against `max_price`
+pub fn interruption_risk(prices: &[f64], max_price: f64) -> RiskLevel {
+    interruption_risk_with(prices, max_price, &RiskThresholds::default())
+}
+
+/// `interruption_risk` with custom thresholds
+///
+/// The latest price sets the level. While prices are rising, the last step is
+/// extrapolated once more and the level raised if that projection crosses the
+/// next threshold, so a fast climb is flagged a sample early.
+pub fn interruption_risk_with(
+    prices: &[f64],
+    max_price: f64,
+    thresholds: &RiskThresholds,
+) -> RiskLevel {
+    let Some(&current) = prices.last() else {
+        return RiskLevel::Low;
+    };
+    if max_price <= 0.0 {
+        return RiskLevel::Low;
+    }
+
+    let level = thresholds.classify(current / max_price);
+    match prices.len().checked_sub(2).map(|i| prices[i]) {
+        Some(previous) if current > previous => {
+            let projected = current + (current - previous);
+            level.max(thresholds.classify(projected / max_price))
+        }
+        _ => level,
+    }
+}
+
+/// Number of notices kept for diagnostics
+pub const NOTICE_HISTORY_CAPACITY: usize = 32;
+
+/// Spot interruption action type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SpotAction {
     /// Instance will be terminated
     Terminate,
@@ -53,13 +227,22 @@ pub enum SpotAction {
 }
 
 impl SpotAction {
+    /// Whether the instance is gone for good (stopped/hibernated ones come back)
+    pub fn terminates_instance(&self) -> bool {
+        matches!(self, Self::Terminate)
+    }
+}
+
+impl std::str::FromStr for SpotAction {
+    type Err = OrchestratorError;
+
     /// Parse from string (as returned by AWS metadata endpoint)
-    pub fn from_str(s: &str) -> Option<Self> {
+    fn from_str(s: &str) -> Result<Self> {
         match s {
-            "terminate" => Some(Self::Terminate),
-            "stop" => Some(Self::Stop),
-            "hibernate" => Some(Self::Hibernate),
-            _ => None,
+            "terminate" => Ok(Self::Terminate),
+            "stop" => Ok(Self::Stop),
+            "hibernate" => Ok(Self::Hibernate),
+            _ => Err(OrchestratorError::Config(format!("Unknown spot action: {}", s))),
         }
     }
 }
@@ -78,24 +261,102 @@ pub struct SpotInterruptionNotice {
 }
 
 /// Raw spot instance action response from AWS
+///
+/// `{"action": "terminate" | "stop" | "hibernate", "time": "2024-01-01T12:00:00Z"}`
 #[derive(Debug, Deserialize)]
 struct SpotInstanceAction {
-    #[serde(rename = "action")]
-    action: String,
+    action: SpotAction,
+    time: DateTime<Utc>,
+}
 
-    #[serde(rename = "time")]
-    time: String,
+impl SpotInterruptionNotice {
+    /// Parse the EC2 `spot/instance-action` JSON document
+    ///
+    /// `seconds_until_action` is measured from `now`; a timestamp already in
+    /// the past (e.g. from clock skew) gives 0 rather than a negative value.
+    pub fn from_instance_action(body: &str, now: DateTime<Utc>) -> Result<Self> {
+        let raw: SpotInstanceAction = serde_json::from_str(body).map_err(|e| {
+            OrchestratorError::Config(format!("Invalid spot instance-action document: {}", e))
+        })?;
+
+        Ok(Self {
+            action: raw.action,
+            time: raw.time,
+            seconds_until_action: (raw.time - now).num_seconds().max(0) as u64,
+        })
+    }
+}
+
+/// Ring buffer of recent notices with duplicate suppression
+///
+/// The metadata endpoint keeps returning the same notice on every poll, and a
+/// flaky endpoint can make it come and go. `observe` decides whether a notice
+/// should be passed on: the first notice always is, and further `Terminate`
+/// notices within the debounce window of the last emitted one are dropped.
+#[derive(Debug, Clone)]
+pub struct NoticeHistory {
+    /// Recently observed notices (oldest first)
+    notices: VecDeque<SpotInterruptionNotice>,
+    /// Maximum number of notices kept
+    capacity: usize,
+    /// Suppression window for repeated `Terminate` notices
+    debounce: Option<Duration>,
+    /// When the last `Terminate` notice was emitted
+    last_terminate: Option<Instant>,
+}
+
+impl NoticeHistory {
+    /// Create a history keeping up to `capacity` notices (at least one)
+    pub fn new(capacity: usize, debounce: Option<Duration>) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            notices: VecDeque::with_capacity(capacity),
+            capacity,
+            debounce,
+            last_terminate: None,
+        }
+    }
+
+    /// Record a notice observed at `now`; returns true if it should be emitted
+    pub fn observe(&mut self, notice: &SpotInterruptionNotice, now: Instant) -> bool {
+        if self.notices.len() == self.capacity {
+            self.notices.pop_front();
+        }
+        self.notices.push_back(notice.clone());
+
+        if notice.action != SpotAction::Terminate {
+            return true;
+        }
+
+        if let (Some(window), Some(last)) = (self.debounce, self.last_terminate)
+            && now.duration_since(last) < window
+        {
+            debug!("Suppressing duplicate termination notice");
+            return false;
+        }
+
+        self.last_terminate = Some(now);
+        true
+    }
+
+    /// Recorded notices, oldest first
+    pub fn notices(&self) -> Vec<SpotInterruptionNotice> {
+        self.notices.iter().cloned().collect()
+    }
 }
 
 /// Spot instance monitor
 ///
 /// Polls the EC2 metadata endpoint for spot interruption notices.
 pub struct SpotMonitor {
-    /// HTTP client for metadata endpoint
-    client: reqwest::Client,
+    /// Metadata client (shared with the stream)
+    imds: ImdsClient,
 
     /// Polling interval
     interval: Duration,
+
+    /// Notices seen by `monitor_stream` (shared with the stream)
+    history: Arc<Mutex<NoticeHistory>>,
 }
 
 impl SpotMonitor {
@@ -107,75 +368,67 @@ impl SpotMonitor {
     /// Create a new spot monitor with custom polling interval
     pub fn with_interval(interval: Duration) -> Self {
         Self {
-            client: reqwest::Client::builder()
-                .timeout(Duration::from_secs(2))
-                .build()
-                .unwrap(),
+            imds: ImdsClient::default(),
             interval,
+            history: Arc::new(Mutex::new(NoticeHistory::new(NOTICE_HISTORY_CAPACITY, None))),
         }
     }
 
+    /// Suppress repeated `Terminate` notices within `window` in `monitor_stream`
+    ///
+    /// The first notice always passes through immediately.
+    pub fn with_debounce(self, window: Duration) -> Self {
+        *self.history.lock().unwrap() = NoticeHistory::new(NOTICE_HISTORY_CAPACITY, Some(window));
+        self
+    }
+
+    /// Poll a different metadata service (e.g. a mock in tests)
+    pub fn with_imds(mut self, imds: ImdsClient) -> Self {
+        self.imds = imds;
+        self
+    }
+
+    /// Recent notices observed by `monitor_stream`, oldest first
+    pub fn recent_notices(&self) -> Vec<SpotInterruptionNotice> {
+        self.history.lock().unwrap().notices()
+    }
+
     /// Check once for a spot interruption notice
     ///
     /// Returns `Ok(None)` if no notice is present (instance is safe).
     /// Returns `Ok(Some(notice))` if a termination notice was found.
     pub async fn check_notice(&self) -> Result<Option<SpotInterruptionNotice>> {
-        let url = format!("{}{}", METADATA_BASE, SPOT_ACTION_ENDPOINT);
-
-        debug!("Checking spot interruption notice at {}", url);
+        debug!("Checking spot interruption notice");
 
-        let response = match self.client.get(&url).send().await {
-            Ok(r) => r,
-            Err(e) => {
-                // HTTP 404 is expected when no notice is present
-                if e.status() == Some(reqwest::StatusCode::NOT_FOUND) {
-                    debug!("No spot interruption notice (404)");
-                    return Ok(None);
-                }
-                // Connection refused means we're not on EC2
-                if e.is_connect() {
-                    warn!("Not running on EC2 (connection refused to metadata endpoint)");
-                    return Ok(None);
-                }
-                return Err(OrchestratorError::Http(e));
+        let notice = match self.imds.spot_action().await {
+            Ok(Some(notice)) => notice,
+            Ok(None) => {
+                debug!("No spot interruption notice (404)");
+                return Ok(None);
             }
-        };
-
-        // Parse the response
-        let action: SpotInstanceAction = response.json().await?;
-
-        let spot_action = SpotAction::from_str(action.action.as_str())
-            .ok_or_else(|| OrchestratorError::Config(format!("Unknown spot action: {}", action.action)))?;
-
-        let time = DateTime::parse_from_rfc3339(&action.time)
-            .map_err(|e| OrchestratorError::Config(format!("Invalid timestamp: {}", e)))?
-            .with_timezone(&Utc);
-
-        let now = Utc::now();
-        let seconds_until = if time > now {
-            (time - now).num_seconds().max(0) as u64
-        } else {
-            0
+            // Connection refused means we're not on EC2
+            Err(OrchestratorError::Http(e)) if e.is_connect() => {
+                warn!("Not running on EC2 (connection refused to metadata endpoint)");
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
         };
 
         info!(
             "Spot interruption notice received: action={:?}, time={}, seconds_until={}",
-            action, time, seconds_until
+            notice.action, notice.time, notice.seconds_until_action
         );
 
-        Ok(Some(SpotInterruptionNotice {
-            action: spot_action,
-            time,
-            seconds_until_action: seconds_until,
-        }))
+        Ok(Some(notice))
     }
 
     /// Start continuous monitoring
     ///
     /// Returns a pinned stream that yields `SpotInterruptionNotice` when a notice is received.
     pub fn monitor_stream(&self) -> Pin<Box<dyn futures::Stream<Item = SpotInterruptionNotice> + Send>> {
-        let client = self.client.clone();
+        let imds = self.imds.clone();
         let interval_duration = self.interval;
+        let history = Arc::clone(&self.history);
 
         Box::pin(async_stream::stream! {
             let mut ticker = interval(interval_duration);
@@ -187,49 +440,24 @@ impl SpotMonitor {
                 iteration_count = iteration_count.wrapping_add(1);
 
                 // Log every 60 iterations (5 minutes) to show we're still alive
-                if iteration_count % 60 == 0 {
+                if iteration_count.is_multiple_of(60) {
                     tracing::debug!("⏰ Spot monitor alive: {} iterations", iteration_count);
                 }
 
-                match ticker.tick().await {
-                    _ => {
-                        // Ticker fired successfully
-                    }
-                }
+                ticker.tick().await;
 
-                let url = format!("{}{}", METADATA_BASE, SPOT_ACTION_ENDPOINT);
-
-                match client.get(&url).send().await {
-                    Ok(response) => {
-                        if response.status() == reqwest::StatusCode::OK {
-                            if let Ok(action) = response.json::<SpotInstanceAction>().await {
-                                if let Some(spot_action) = SpotAction::from_str(&action.action) {
-                                    if let Ok(time) = DateTime::parse_from_rfc3339(&action.time) {
-                                        let time = time.with_timezone(&Utc);
-                                        let now = Utc::now();
-                                        let seconds_until = if time > now {
-                                            (time - now).num_seconds().max(0) as u64
-                                        } else {
-                                            0
-                                        };
-
-                                        tracing::info!("🔔 Spot interruption notice: {:?}", action.action);
-                                        yield SpotInterruptionNotice {
-                                            action: spot_action,
-                                            time,
-                                            seconds_until_action: seconds_until,
-                                        };
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        // Expected: 404 when no spot notice, connect error when not on EC2
-                        if e.status() != Some(reqwest::StatusCode::NOT_FOUND) && !e.is_connect() {
-                            tracing::warn!("Error checking spot notice: {}", e);
+                match imds.spot_action().await {
+                    Ok(Some(notice)) => {
+                        let emit = history.lock().unwrap().observe(&notice, Instant::now());
+                        if emit {
+                            tracing::info!("🔔 Spot interruption notice: {:?}", notice.action);
+                            yield notice;
                         }
                     }
+                    Ok(None) => {}
+                    // Expected: connect error when not on EC2
+                    Err(OrchestratorError::Http(e)) if e.is_connect() => {}
+                    Err(e) => tracing::warn!("Error checking spot notice: {}", e),
                 }
             }
             // This line is unreachable, but prevents compiler from optimizing the loop away
@@ -241,6 +469,78 @@ impl SpotMonitor {
     }
 }
 
+/// Reacts to spot interruption notices delivered by `run_monitor`
+///
+/// Return `ControlFlow::Break(())` to stop monitoring after this notice.
+/// The next notice is not polled for until the returned future completes.
+pub trait MonitorHandler: Send {
+    fn on_notice(&mut self, notice: &SpotInterruptionNotice) -> impl Future<Output = ControlFlow<()>>;
+}
+
+/// Logs every notice and keeps monitoring
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogHandler;
+
+impl MonitorHandler for LogHandler {
+    async fn on_notice(&mut self, notice: &SpotInterruptionNotice) -> ControlFlow<()> {
+        warn!(
+            "SPOT INTERRUPTION NOTICE: {:?} in {} seconds",
+            notice.action, notice.seconds_until_action
+        );
+        ControlFlow::Continue(())
+    }
+}
+
+/// Passes every notice to a closure
+pub struct CallbackHandler<F> {
+    callback: F,
+}
+
+impl<F> CallbackHandler<F>
+where
+    F: FnMut(&SpotInterruptionNotice) -> ControlFlow<()> + Send,
+{
+    pub fn new(callback: F) -> Self {
+        Self { callback }
+    }
+}
+
+impl<F> MonitorHandler for CallbackHandler<F>
+where
+    F: FnMut(&SpotInterruptionNotice) -> ControlFlow<()> + Send,
+{
+    async fn on_notice(&mut self, notice: &SpotInterruptionNotice) -> ControlFlow<()> {
+        (self.callback)(notice)
+    }
+}
+
+/// Poll for notices every `interval` and hand each one to `handler`
+///
+/// Returns the notice the handler stopped on.
+pub async fn run_monitor(
+    interval: Duration,
+    handler: &mut impl MonitorHandler,
+) -> Option<SpotInterruptionNotice> {
+    let monitor = SpotMonitor::with_interval(interval);
+    dispatch_notices(monitor.monitor_stream(), handler).await
+}
+
+/// Hand each notice from `notices` to `handler` until it stops or the stream ends
+///
+/// Returns the notice the handler stopped on, or `None` if the stream ended.
+pub async fn dispatch_notices(
+    notices: impl Stream<Item = SpotInterruptionNotice>,
+    handler: &mut impl MonitorHandler,
+) -> Option<SpotInterruptionNotice> {
+    let mut notices = std::pin::pin!(notices);
+    while let Some(notice) = notices.next().await {
+        if handler.on_notice(&notice).await.is_break() {
+            return Some(notice);
+        }
+    }
+    None
+}
+
 impl Default for SpotMonitor {
     fn default() -> Self {
         Self::new()
@@ -253,9 +553,194 @@ mod tests {
 
     #[test]
     fn test_spot_action_from_str() {
-        assert_eq!(SpotAction::from_str("terminate"), Some(SpotAction::Terminate));
-        assert_eq!(SpotAction::from_str("stop"), Some(SpotAction::Stop));
-        assert_eq!(SpotAction::from_str("hibernate"), Some(SpotAction::Hibernate));
-        assert_eq!(SpotAction::from_str("unknown"), None);
+        assert_eq!("terminate".parse::<SpotAction>().ok(), Some(SpotAction::Terminate));
+        assert_eq!("stop".parse::<SpotAction>().ok(), Some(SpotAction::Stop));
+        assert_eq!("hibernate".parse::<SpotAction>().ok(), Some(SpotAction::Hibernate));
+        assert_eq!("unknown".parse::<SpotAction>().ok(), None);
+    }
+
+    #[test]
+    fn test_interruption_risk() {
+        // Flat and well below the max
+        assert_eq!(interruption_risk(&[0.30, 0.30, 0.30], 1.0), RiskLevel::Low);
+        // Close to the max
+        assert_eq!(interruption_risk(&[0.72, 0.75], 1.0), RiskLevel::Medium);
+        assert_eq!(interruption_risk(&[0.95, 0.92], 1.0), RiskLevel::High);
+        // Falling back from a spike
+        assert_eq!(interruption_risk(&[0.95, 0.60], 1.0), RiskLevel::Low);
+        // A fast climb is flagged one sample early
+        assert_eq!(interruption_risk(&[0.40, 0.55, 0.65], 1.0), RiskLevel::Medium);
+        assert_eq!(interruption_risk(&[0.60, 0.70, 0.82], 1.0), RiskLevel::High);
+        // No data
+        assert_eq!(interruption_risk(&[], 1.0), RiskLevel::Low);
+        assert_eq!(interruption_risk(&[0.5], 0.0), RiskLevel::Low);
+
+        let strict = RiskThresholds { medium: 0.5, high: 0.6 };
+        assert_eq!(interruption_risk_with(&[0.55, 0.55], 1.0, &strict), RiskLevel::Medium);
+    }
+
+    #[test]
+    fn test_spot_action_terminates_instance() {
+        assert!(SpotAction::Terminate.terminates_instance());
+        assert!(!SpotAction::Stop.terminates_instance());
+        assert!(!SpotAction::Hibernate.terminates_instance());
+    }
+
+    #[test]
+    fn test_notice_from_instance_action() {
+        let now = DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z").unwrap().with_timezone(&Utc);
+
+        for (raw, action) in [
+            ("terminate", SpotAction::Terminate),
+            ("stop", SpotAction::Stop),
+            ("hibernate", SpotAction::Hibernate),
+        ] {
+            let body = format!(r#"{{"action": "{}", "time": "2024-06-01T12:02:00Z"}}"#, raw);
+            let notice = SpotInterruptionNotice::from_instance_action(&body, now).unwrap();
+            assert_eq!(notice.action, action);
+            assert_eq!(notice.seconds_until_action, 120);
+        }
+
+        let body = r#"{"action": "reboot", "time": "2024-06-01T12:02:00Z"}"#;
+        assert!(SpotInterruptionNotice::from_instance_action(body, now).is_err());
+        let body = r#"{"action": "terminate", "time": "soon"}"#;
+        assert!(SpotInterruptionNotice::from_instance_action(body, now).is_err());
+    }
+
+    #[test]
+    fn test_notice_skewed_clock_clamps_to_zero() {
+        // Local clock runs ahead of EC2's: the action time is already "past"
+        let now = DateTime::parse_from_rfc3339("2024-06-01T12:05:00Z").unwrap().with_timezone(&Utc);
+        let body = r#"{"action": "terminate", "time": "2024-06-01T12:02:00+00:00"}"#;
+
+        let notice = SpotInterruptionNotice::from_instance_action(body, now).unwrap();
+        assert_eq!(notice.seconds_until_action, 0);
+    }
+
+    #[test]
+    fn test_cloud_provider_grace_periods() {
+        assert_eq!("AWS".parse::<CloudProvider>().unwrap(), CloudProvider::Aws);
+        assert!("oracle".parse::<CloudProvider>().is_err());
+
+        assert_eq!(CloudProvider::from_sys_vendor("Amazon EC2\n"), Some(CloudProvider::Aws));
+        assert_eq!(CloudProvider::from_sys_vendor("Google"), Some(CloudProvider::Gcp));
+        assert_eq!(CloudProvider::from_sys_vendor("Microsoft Corporation"), Some(CloudProvider::Azure));
+        assert_eq!(CloudProvider::from_sys_vendor("QEMU"), None);
+
+        assert_eq!(CloudProvider::Aws.grace_period().as_secs(), 120);
+        assert_eq!(CloudProvider::Gcp.grace_period().as_secs(), 30);
+    }
+
+    #[test]
+    fn test_validate_drain_timeout() {
+        let aws = CloudProvider::Aws.grace_period();
+        let gcp = CloudProvider::Gcp.grace_period();
+
+        assert!(validate_drain_timeout(Duration::from_secs(115), aws).is_ok());
+        assert!(validate_drain_timeout(Duration::from_secs(120), aws).is_err());
+        // The AWS default is far too long for GCP's 30s notice
+        let err = validate_drain_timeout(Duration::from_secs(115), gcp).unwrap_err();
+        assert!(err.to_string().contains("30s"));
+    }
+
+    #[tokio::test]
+    async fn test_callback_handler_receives_notices() {
+        let notices = futures::stream::iter([
+            notice(SpotAction::Stop),
+            notice(SpotAction::Terminate),
+            notice(SpotAction::Hibernate),
+        ]);
+
+        let mut seen = Vec::new();
+        let mut handler = CallbackHandler::new(|notice: &SpotInterruptionNotice| {
+            seen.push(notice.action);
+            if notice.action.terminates_instance() {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+
+        let stopped_on = dispatch_notices(notices, &mut handler).await;
+        assert_eq!(stopped_on.map(|n| n.action), Some(SpotAction::Terminate));
+        assert_eq!(seen, [SpotAction::Stop, SpotAction::Terminate]);
+
+        let mut handler = LogHandler;
+        let notices = futures::stream::iter([notice(SpotAction::Terminate)]);
+        assert!(dispatch_notices(notices, &mut handler).await.is_none());
+    }
+
+    fn notice(action: SpotAction) -> SpotInterruptionNotice {
+        SpotInterruptionNotice {
+            action,
+            time: Utc::now(),
+            seconds_until_action: GRACE_PERIOD_SECONDS,
+        }
+    }
+
+    #[test]
+    fn test_notice_history_debounces_terminate() {
+        let mut history = NoticeHistory::new(NOTICE_HISTORY_CAPACITY, Some(Duration::from_secs(30)));
+        let start = Instant::now();
+
+        // Polls every 5s; flapping endpoint drops the notice once
+        let sequence = [
+            (0, SpotAction::Terminate),
+            (5, SpotAction::Terminate),
+            (10, SpotAction::Stop),
+            (20, SpotAction::Terminate),
+            (35, SpotAction::Terminate),
+            (40, SpotAction::Terminate),
+        ];
+        let emitted: Vec<(u64, SpotAction)> = sequence
+            .iter()
+            .filter(|(t, action)| history.observe(&notice(*action), start + Duration::from_secs(*t)))
+            .copied()
+            .collect();
+
+        assert_eq!(
+            emitted,
+            vec![
+                (0, SpotAction::Terminate),
+                (10, SpotAction::Stop),
+                (35, SpotAction::Terminate),
+            ]
+        );
+        assert_eq!(history.notices().len(), sequence.len());
+    }
+
+    #[test]
+    fn test_notice_history_without_debounce() {
+        let mut history = NoticeHistory::new(2, None);
+        let now = Instant::now();
+
+        assert!(history.observe(&notice(SpotAction::Terminate), now));
+        assert!(history.observe(&notice(SpotAction::Terminate), now));
+        assert!(history.observe(&notice(SpotAction::Hibernate), now));
+
+        // Capacity bounds the ring buffer
+        let recent = history.notices();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[1].action, SpotAction::Hibernate);
+    }
+
+    #[test]
+    fn test_notice_history_zero_capacity_keeps_latest() {
+        let mut history = NoticeHistory::new(0, None);
+        let now = Instant::now();
+
+        for action in [SpotAction::Terminate, SpotAction::Stop, SpotAction::Hibernate] {
+            assert!(history.observe(&notice(action), now));
+        }
+
+        let recent = history.notices();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].action, SpotAction::Hibernate);
+    }
+
+    #[test]
+    fn test_monitor_with_debounce_starts_empty() {
+        let monitor = SpotMonitor::new().with_debounce(Duration::from_secs(30));
+        assert!(monitor.recent_notices().is_empty());
     }
 }
@@ -0,0 +1,167 @@
+//! Drain-aware reverse proxy in front of vLLM
+//!
+//! Waiting for in-flight requests only works if new ones stop arriving. ELB
+//! deregistration stops the load balancer from opening new connections, but
+//! requests on existing keep-alive connections, direct peer traffic and the
+//! deregistration delay itself can keep the running count above zero under
+//! load. When the agent fronts vLLM with this proxy, the drain manager flips
+//! a shared `DrainGate` and the proxy answers new requests with
+//! `503 Service Unavailable` (and `Retry-After`) so clients and the load
+//! balancer retry elsewhere, while requests already forwarded run to
+//! completion.
+
+use crate::error::Result;
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{info, warn};
+
+/// Seconds clients are told to wait before retrying a rejected request
+pub const RETRY_AFTER_SECS: u64 = 1;
+
+/// Shared flag telling the proxy to reject new requests
+///
+/// Cloning is cheap and clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct DrainGate {
+    draining: Arc<AtomicBool>,
+}
+
+impl DrainGate {
+    /// Create an open gate (requests are forwarded)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start rejecting new requests
+    pub fn start_draining(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    /// Forward requests again (e.g. after resuming from a stop)
+    pub fn reopen(&self) {
+        self.draining.store(false, Ordering::SeqCst);
+    }
+
+    /// Whether new requests are being rejected
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Clone)]
+struct ProxyState {
+    upstream: String,
+    gate: DrainGate,
+    client: reqwest::Client,
+}
+
+/// Build a router forwarding every request to `upstream` until the gate closes
+pub fn proxy_router(upstream: impl Into<String>, gate: DrainGate) -> Router {
+    Router::new().fallback(forward).with_state(ProxyState {
+        upstream: upstream.into().trim_end_matches('/').to_string(),
+        gate,
+        client: reqwest::Client::new(),
+    })
+}
+
+/// Serve the proxy on `port` until the task is cancelled
+pub async fn serve_proxy(port: u16, upstream: impl Into<String>, gate: DrainGate) -> Result<()> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let router = proxy_router(upstream, gate);
+    info!("Drain proxy listening on {}", addr);
+    axum::serve(listener, router).await?;
+    Ok(())
+}
+
+async fn forward(State(state): State<ProxyState>, request: Request) -> Response {
+    if state.gate.is_draining() {
+        let mut response = (StatusCode::SERVICE_UNAVAILABLE, "Node is draining").into_response();
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(RETRY_AFTER_SECS));
+        return response;
+    }
+
+    let (parts, body) = request.into_parts();
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or("/");
+    let url = format!("{}{}", state.upstream, path);
+
+    let mut headers = parts.headers;
+    headers.remove(header::HOST);
+
+    let upstream_response = state
+        .client
+        .request(parts.method, &url)
+        .headers(headers)
+        .body(reqwest::Body::wrap_stream(body.into_data_stream()))
+        .send()
+        .await;
+
+    match upstream_response {
+        Ok(upstream) => {
+            let mut response = Response::builder().status(upstream.status());
+            if let Some(headers) = response.headers_mut() {
+                headers.extend(upstream.headers().clone());
+                // Hop-by-hop headers; hyper re-frames the streamed body itself
+                headers.remove(header::CONNECTION);
+                headers.remove(header::TRANSFER_ENCODING);
+            }
+            // Stream the body so token streaming (SSE) passes through
+            response
+                .body(Body::from_stream(upstream.bytes_stream()))
+                .unwrap_or_else(|_| StatusCode::BAD_GATEWAY.into_response())
+        }
+        Err(e) => {
+            warn!(url = %url, "Proxy request to vLLM failed: {}", e);
+            StatusCode::BAD_GATEWAY.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_proxy_rejects_new_requests_while_draining() {
+        let vllm = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("completion"))
+            .mount(&vllm)
+            .await;
+
+        let gate = DrainGate::new();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let router = proxy_router(vllm.uri(), gate.clone());
+        tokio::spawn(axum::serve(listener, router).into_future());
+
+        let client = reqwest::Client::new();
+        let url = format!("{}/v1/completions", base);
+
+        let response = client.post(&url).body("{}").send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "completion");
+
+        gate.start_draining();
+
+        let response = client.post(&url).body("{}").send().await.unwrap();
+        assert_eq!(response.status(), 503);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+        assert_eq!(vllm.received_requests().await.unwrap().len(), 1);
+    }
+}
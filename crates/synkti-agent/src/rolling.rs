@@ -0,0 +1,190 @@
+//! Rolling restart across a set of nodes
+//!
+//! Rolls a new model or image out without dropping capacity: nodes are
+//! restarted `batch_size` at a time, and the next batch only starts once every
+//! node in the current one is healthy and registered again. A failure stops
+//! the rollout so at most one batch is ever out of service; the rest of the
+//! failing batch is still allowed to finish, so no node is left half-restarted.
+
+use crate::error::{AgentError as OrchestratorError, Result};
+use futures::future::join_all;
+use std::future::Future;
+use tracing::{info, warn};
+
+/// Node operations a rolling restart needs
+pub trait RestartableNode {
+    /// Identifier used in logs and the report (e.g. the instance ID)
+    fn id(&self) -> &str;
+
+    /// Drain and deregister the node, then swap its container
+    fn restart(&mut self) -> impl Future<Output = Result<()>>;
+
+    /// Wait until the node is healthy and registered again
+    fn wait_ready(&mut self) -> impl Future<Output = Result<()>>;
+}
+
+/// Restart `nodes` in batches of at most `batch_size`, in order
+///
+/// Returns the node IDs of each completed batch. Stops after the first batch
+/// in which a node fails to restart or come back, returning every failure in
+/// that batch as `RollingRestart`; later batches are left untouched.
+pub async fn rolling_restart<N: RestartableNode>(
+    nodes: &mut [N],
+    batch_size: usize,
+) -> Result<Vec<Vec<String>>> {
+    if batch_size == 0 {
+        return Err(OrchestratorError::Config(
+            "Rolling restart batch size must be at least 1".to_string(),
+        ));
+    }
+
+    let mut batches = Vec::new();
+    for (index, batch) in nodes.chunks_mut(batch_size).enumerate() {
+        let ids: Vec<String> = batch.iter().map(|node| node.id().to_string()).collect();
+        info!(batch = index + 1, nodes = ?ids, "Restarting batch");
+
+        let results = join_all(batch.iter_mut().map(|node| async move {
+            node.restart().await?;
+            node.wait_ready().await
+        }))
+        .await;
+
+        let failures: Vec<(String, OrchestratorError)> = ids
+            .iter()
+            .cloned()
+            .zip(results)
+            .filter_map(|(id, result)| result.err().map(|e| (id, e)))
+            .collect();
+        if !failures.is_empty() {
+            warn!(batch = index + 1, failed = failures.len(), "Batch failed, stopping rolling restart");
+            return Err(OrchestratorError::RollingRestart { failures });
+        }
+
+        batches.push(ids);
+    }
+
+    info!(batches = batches.len(), "Rolling restart completed");
+    Ok(batches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct Cluster {
+        events: Vec<String>,
+        out_of_service: usize,
+        max_out_of_service: usize,
+    }
+
+    struct MockNode {
+        id: String,
+        cluster: Arc<Mutex<Cluster>>,
+        fail_ready: bool,
+        fail_restart: bool,
+    }
+
+    impl MockNode {
+        fn new(id: &str, cluster: &Arc<Mutex<Cluster>>) -> Self {
+            Self {
+                id: id.to_string(),
+                cluster: Arc::clone(cluster),
+                fail_ready: false,
+                fail_restart: false,
+            }
+        }
+    }
+
+    impl RestartableNode for MockNode {
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        async fn restart(&mut self) -> Result<()> {
+            if self.fail_restart {
+                return Err(OrchestratorError::Docker(format!("{} failed to restart", self.id)));
+            }
+            {
+                let mut cluster = self.cluster.lock().unwrap();
+                cluster.events.push(format!("restart {}", self.id));
+                cluster.out_of_service += 1;
+                cluster.max_out_of_service = cluster.max_out_of_service.max(cluster.out_of_service);
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            Ok(())
+        }
+
+        async fn wait_ready(&mut self) -> Result<()> {
+            if self.fail_ready {
+                return Err(OrchestratorError::HealthCheck(format!("{} never became healthy", self.id)));
+            }
+            let mut cluster = self.cluster.lock().unwrap();
+            cluster.events.push(format!("ready {}", self.id));
+            cluster.out_of_service -= 1;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rolling_restart_batches() {
+        let cluster = Arc::new(Mutex::new(Cluster::default()));
+        let mut nodes: Vec<MockNode> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|id| MockNode::new(id, &cluster))
+            .collect();
+
+        let batches = rolling_restart(&mut nodes, 2).await.unwrap();
+        assert_eq!(batches, vec![vec!["a", "b"], vec!["c", "d"], vec!["e"]]);
+
+        let cluster = cluster.lock().unwrap();
+        assert_eq!(cluster.max_out_of_service, 2);
+        // Each batch comes back before the next one goes down
+        let position = |event: &str| cluster.events.iter().position(|e| e == event).unwrap();
+        assert!(position("ready a") < position("restart c"));
+        assert!(position("ready b") < position("restart c"));
+        assert!(position("ready d") < position("restart e"));
+    }
+
+    #[tokio::test]
+    async fn test_rolling_restart_stops_on_failure() {
+        let cluster = Arc::new(Mutex::new(Cluster::default()));
+        let mut nodes = vec![
+            MockNode::new("a", &cluster),
+            MockNode::new("b", &cluster),
+            MockNode::new("c", &cluster),
+        ];
+        nodes[1].fail_ready = true;
+
+        assert!(rolling_restart(&mut nodes, 1).await.is_err());
+        assert!(!cluster.lock().unwrap().events.contains(&"restart c".to_string()));
+        assert!(rolling_restart(&mut nodes, 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rolling_restart_finishes_batch_before_stopping() {
+        let cluster = Arc::new(Mutex::new(Cluster::default()));
+        let mut nodes = vec![
+            MockNode::new("a", &cluster),
+            MockNode::new("b", &cluster),
+            MockNode::new("c", &cluster),
+        ];
+        // b fails straight away while a is still restarting
+        nodes[1].fail_restart = true;
+
+        match rolling_restart(&mut nodes, 2).await {
+            Err(OrchestratorError::RollingRestart { failures }) => {
+                let ids: Vec<&str> = failures.iter().map(|(id, _)| id.as_str()).collect();
+                assert_eq!(ids, ["b"]);
+            }
+            other => panic!("expected RollingRestart, got {:?}", other),
+        }
+
+        let cluster = cluster.lock().unwrap();
+        assert!(cluster.events.contains(&"ready a".to_string()));
+        assert!(!cluster.events.contains(&"restart c".to_string()));
+        assert_eq!(cluster.out_of_service, 0);
+    }
+}
@@ -0,0 +1,333 @@
+//! Agent HTTP server
+//!
+//! Serves node-local endpoints for operators and load balancers:
+//! - `GET /health`: liveness probe (always 200 while the agent runs)
+//! - `GET /readyz`: readiness probe; 200 once vLLM lists a loaded model, 503
+//!   while it is loading or once the node starts draining
+//! - `GET /status`: node status (`synkti_core::NodeStatus`) as JSON
+//! - `GET /logs`: recent agent log records as JSON (oldest first)
+//! - `GET /metrics`: agent metrics in the Prometheus text format
+//! - `GET /gpu`: per-GPU memory and utilization from nvidia-smi (503 if unavailable)
+//!
+//! `/health` only says the agent process is up, which is true long before a
+//! model finishes loading. Load balancer target-group health checks should
+//! use `/readyz` so traffic arrives only once the node can serve it.
+
+use crate::command::CommandExecutor;
+use crate::error::Result;
+use crate::gpu::{self, GpuUtil};
+use crate::logs::{LogBuffer, LogRecord};
+use crate::metrics::AgentMetrics;
+use crate::proxy::DrainGate;
+use crate::vllm::{RequestCount, VllmClient};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+use synkti_core::{HealthStatus, NodeStatus, SpotState};
+use tracing::info;
+
+/// Shared state for request handlers
+#[derive(Clone)]
+pub struct ServerState {
+    /// Captured agent logs
+    pub logs: LogBuffer,
+    /// Agent metrics
+    pub metrics: AgentMetrics,
+    /// Client for the local vLLM server, probed by `/readyz` and `/status`
+    pub vllm: VllmClient,
+    /// Instance ID reported by `/status`
+    pub instance_id: String,
+    /// Instance type reported by `/status`, when known
+    pub instance_type: Option<String>,
+    /// When the agent started, for uptime
+    pub started_at: Instant,
+    /// Drain gate; once it closes `/readyz` fails and `/status` reports draining
+    pub drain_gate: Option<DrainGate>,
+    /// Runs `nvidia-smi` for `/gpu`
+    pub executor: Arc<dyn CommandExecutor>,
+}
+
+/// Build the agent router
+pub fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/readyz", get(readyz))
+        .route("/status", get(status))
+        .route("/logs", get(logs))
+        .route("/metrics", get(metrics))
+        .route("/gpu", get(gpu_utilization))
+        .with_state(state)
+}
+
+/// Serve the agent endpoints on `port` until the task is cancelled
+pub async fn serve(port: u16, state: ServerState) -> Result<()> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("Agent HTTP server listening on {}", addr);
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
+
+async fn health() -> &'static str {
+    "ok"
+}
+
+async fn readyz(State(state): State<ServerState>) -> (StatusCode, &'static str) {
+    if state.drain_gate.as_ref().is_some_and(DrainGate::is_draining) {
+        return (StatusCode::SERVICE_UNAVAILABLE, "draining");
+    }
+    match state.vllm.list_models().await {
+        Ok(models) if !models.is_empty() => (StatusCode::OK, "ready"),
+        _ => (StatusCode::SERVICE_UNAVAILABLE, "loading"),
+    }
+}
+
+async fn status(State(state): State<ServerState>) -> Json<NodeStatus> {
+    let model = state
+        .vllm
+        .list_models()
+        .await
+        .ok()
+        .and_then(|models| models.into_iter().next());
+    let running_requests = state
+        .vllm
+        .get_running_requests()
+        .await
+        .ok()
+        .and_then(RequestCount::known);
+    let draining = state.drain_gate.as_ref().is_some_and(DrainGate::is_draining);
+
+    let (spot_state, health) = if draining {
+        (SpotState::Draining, HealthStatus::Draining)
+    } else if model.is_some() {
+        (SpotState::Active, HealthStatus::Healthy)
+    } else {
+        (SpotState::Active, HealthStatus::Starting)
+    };
+
+    Json(NodeStatus {
+        instance_id: state.instance_id,
+        instance_type: state.instance_type,
+        spot_state,
+        health,
+        model,
+        running_requests,
+        uptime_seconds: state.started_at.elapsed().as_secs(),
+    })
+}
+
+async fn logs(State(state): State<ServerState>) -> Json<Vec<LogRecord>> {
+    Json(state.logs.recent_logs())
+}
+
+async fn metrics(State(state): State<ServerState>) -> std::result::Result<String, StatusCode> {
+    state
+        .metrics
+        .render()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn gpu_utilization(
+    State(state): State<ServerState>,
+) -> std::result::Result<Json<Vec<GpuUtil>>, (StatusCode, String)> {
+    gpu::query_gpu_utilization(state.executor.as_ref())
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::MockExecutor;
+    use chrono::Utc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn test_state(logs: LogBuffer, metrics: AgentMetrics, vllm_url: &str) -> ServerState {
+        ServerState {
+            logs,
+            metrics,
+            vllm: VllmClient::new(vllm_url),
+            instance_id: "i-0abc".to_string(),
+            instance_type: Some("g5.xlarge".to_string()),
+            started_at: Instant::now(),
+            drain_gate: None,
+            executor: Arc::new(MockExecutor::new()),
+        }
+    }
+
+    async fn spawn_server(state: ServerState) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(axum::serve(listener, router(state)).into_future());
+        base
+    }
+
+    #[tokio::test]
+    async fn test_logs_endpoint() {
+        let logs = LogBuffer::new(10);
+        logs.push(LogRecord {
+            timestamp: Utc::now(),
+            level: "INFO".to_string(),
+            target: "synkti_agent".to_string(),
+            message: "hello".to_string(),
+        });
+
+        let metrics = AgentMetrics::new().unwrap();
+        metrics.record_failover(true);
+        let base = spawn_server(test_state(logs, metrics, "http://127.0.0.1:1")).await;
+
+        let health = reqwest::get(format!("{}/health", base)).await.unwrap();
+        assert!(health.status().is_success());
+
+        let records: Vec<LogRecord> = reqwest::get(format!("{}/logs", base))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].message, "hello");
+
+        let text = reqwest::get(format!("{}/metrics", base))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(text.contains("synkti_failovers_total{result=\"success\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_readyz_waits_for_model() {
+        let vllm = MockServer::start().await;
+        let base = spawn_server(test_state(
+            LogBuffer::new(10),
+            AgentMetrics::new().unwrap(),
+            &vllm.uri(),
+        ))
+        .await;
+
+        // Model still loading: vLLM isn't answering /v1/models yet
+        let response = reqwest::get(format!("{}/readyz", base)).await.unwrap();
+        assert_eq!(response.status(), 503);
+        assert_eq!(response.text().await.unwrap(), "loading");
+        let health = reqwest::get(format!("{}/health", base)).await.unwrap();
+        assert!(health.status().is_success());
+
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{"id": "meta-llama/Llama-3.1-8B"}]
+            })))
+            .mount(&vllm)
+            .await;
+
+        let response = reqwest::get(format!("{}/readyz", base)).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "ready");
+    }
+
+    #[tokio::test]
+    async fn test_readyz_fails_while_draining() {
+        let vllm = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{"id": "meta-llama/Llama-3.1-8B"}]
+            })))
+            .mount(&vllm)
+            .await;
+
+        let gate = DrainGate::new();
+        let mut state = test_state(LogBuffer::new(10), AgentMetrics::new().unwrap(), &vllm.uri());
+        state.drain_gate = Some(gate.clone());
+        let base = spawn_server(state).await;
+
+        let response = reqwest::get(format!("{}/readyz", base)).await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        // The model is still loaded, but the load balancer should stop routing here
+        gate.start_draining();
+        let response = reqwest::get(format!("{}/readyz", base)).await.unwrap();
+        assert_eq!(response.status(), 503);
+        assert_eq!(response.text().await.unwrap(), "draining");
+    }
+
+    #[tokio::test]
+    async fn test_status_endpoint() {
+        let vllm = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{"id": "meta-llama/Llama-3.1-8B"}]
+            })))
+            .mount(&vllm)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/metrics"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string("vllm:num_requests_running 2.0\n"),
+            )
+            .mount(&vllm)
+            .await;
+
+        let gate = DrainGate::new();
+        let mut state = test_state(LogBuffer::new(10), AgentMetrics::new().unwrap(), &vllm.uri());
+        state.drain_gate = Some(gate.clone());
+        let base = spawn_server(state).await;
+
+        let status: NodeStatus = reqwest::get(format!("{}/status", base))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(status.instance_id, "i-0abc");
+        assert_eq!(status.instance_type.as_deref(), Some("g5.xlarge"));
+        assert_eq!(status.spot_state, SpotState::Active);
+        assert_eq!(status.health, HealthStatus::Healthy);
+        assert_eq!(status.model.as_deref(), Some("meta-llama/Llama-3.1-8B"));
+        assert_eq!(status.running_requests, Some(2));
+
+        gate.start_draining();
+        let status: NodeStatus = reqwest::get(format!("{}/status", base))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(status.spot_state, SpotState::Draining);
+    }
+
+    #[tokio::test]
+    async fn test_gpu_endpoint() {
+        let smi = MockExecutor::new();
+        smi.push_reply(true, "0, 1024, 50\n1, [N/A], [N/A]\n", "");
+        smi.push_reply(false, "", "NVIDIA-SMI has failed");
+
+        let metrics = AgentMetrics::new().unwrap();
+        let mut state = test_state(LogBuffer::new(10), metrics, "http://127.0.0.1:1");
+        state.executor = Arc::new(smi.clone());
+        let base = spawn_server(state).await;
+
+        let gpus: Vec<GpuUtil> = reqwest::get(format!("{}/gpu", base))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].mem_used_mb, Some(1024.0));
+        assert_eq!(gpus[1].util_percent, None);
+
+        let response = reqwest::get(format!("{}/gpu", base)).await.unwrap();
+        assert_eq!(response.status(), 503);
+        assert!(smi.calls()[0].starts_with("nvidia-smi"));
+    }
+}
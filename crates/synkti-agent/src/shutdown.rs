@@ -0,0 +1,644 @@
+//! Spot termination handling
+//!
+//! Ties the pieces of a graceful shutdown together when a spot notice
+//! arrives: tell the fleet we are draining, drain in-flight requests within
+//! the remaining grace period, stop the vLLM container, then report the final
+//! status.
+//!
+//! `Stop` and `Hibernate` notices drain the same way, but the instance comes
+//! back afterwards: the container is left for the OS to stop (or hibernate),
+//! a resume marker recording the boot ID is written, and the first agent
+//! start after a reboot resumes serving.
+
+use crate::command::{CommandExecutor, SystemExecutor, to_args};
+use crate::drain::{DEFAULT_MAX_PROBE_FAILURES, DrainManager, DrainResult, SpawnTimeEstimator};
+use crate::error::Result;
+use crate::fleet::{FleetClient, NodeState, NodeStatusUpdate};
+use crate::metrics::AgentMetrics;
+use crate::monitor::{MonitorHandler, SpotInterruptionNotice};
+use crate::proxy::DrainGate;
+use crate::vllm::VllmClient;
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+/// Seconds kept back from the grace period for stopping the container
+pub const STOP_BUFFER_SECS: u64 = 5;
+
+/// How long a resume waits for vLLM to become healthy before giving up on
+/// timing it (large models can take minutes to load)
+pub const RESUME_READY_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Interval between health checks while waiting for a resumed vLLM
+const RESUME_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Kernel boot ID, regenerated on every boot (and on resume from hibernation)
+pub const BOOT_ID_PATH: &str = "/proc/sys/kernel/random/boot_id";
+
+/// Handles spot termination notices on this node
+pub struct TerminationHandler {
+    /// Instance ID reported to the fleet API
+    instance_id: String,
+
+    /// Client for the local vLLM server
+    vllm_client: VllmClient,
+
+    /// Fleet API client (if configured)
+    fleet: Option<FleetClient>,
+
+    /// vLLM container to stop once drained (if managed by the agent)
+    container_name: Option<String>,
+
+    /// Metrics updated with the failover outcome
+    metrics: Option<AgentMetrics>,
+
+    /// Drain proxy gate to close when draining starts
+    drain_gate: Option<DrainGate>,
+
+    /// Consecutive polls with vLLM unreachable before it is assumed gone
+    max_probe_failures: u32,
+
+    /// Upper bound on the drain, on top of the notice's own deadline
+    drain_timeout: Option<Duration>,
+
+    /// Replacement spawn times to leave room for (none: drain until the stop buffer)
+    spawn_estimator: Option<SpawnTimeEstimator>,
+
+    /// File the spawn estimator is saved to after recording a resume
+    spawn_history: Option<PathBuf>,
+
+    /// File marking that the node was suspended by a stop/hibernate notice
+    resume_marker: Option<PathBuf>,
+
+    /// File holding the current boot ID, stored in the resume marker
+    boot_id_path: PathBuf,
+
+    /// Runs docker commands
+    executor: Box<dyn CommandExecutor>,
+}
+
+impl TerminationHandler {
+    /// Create a handler draining the given vLLM server
+    pub fn new(instance_id: impl Into<String>, vllm_client: VllmClient) -> Self {
+        Self {
+            instance_id: instance_id.into(),
+            vllm_client,
+            fleet: None,
+            container_name: None,
+            metrics: None,
+            drain_gate: None,
+            max_probe_failures: DEFAULT_MAX_PROBE_FAILURES,
+            drain_timeout: None,
+            spawn_estimator: None,
+            spawn_history: None,
+            resume_marker: None,
+            boot_id_path: PathBuf::from(BOOT_ID_PATH),
+            executor: Box::new(SystemExecutor),
+        }
+    }
+
+    /// Run docker commands through `executor` (e.g. a `MockExecutor` in tests)
+    pub fn with_executor(mut self, executor: impl CommandExecutor + 'static) -> Self {
+        self.executor = Box::new(executor);
+        self
+    }
+
+    /// Report drain progress to the fleet API
+    pub fn with_fleet(mut self, fleet: FleetClient) -> Self {
+        self.fleet = Some(fleet);
+        self
+    }
+
+    /// Record failover outcomes and drain times
+    pub fn with_metrics(mut self, metrics: AgentMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Close `gate` while draining: the drain proxy rejects new requests and
+    /// `/readyz` fails
+    pub fn with_drain_gate(mut self, gate: DrainGate) -> Self {
+        self.drain_gate = Some(gate);
+        self
+    }
+
+    /// Never drain longer than `timeout`, even if the notice leaves more time
+    ///
+    /// Validate it first with `monitor::validate_drain_timeout`.
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = Some(timeout);
+        self
+    }
+
+    /// Stop draining early enough for a replacement to come up in time
+    ///
+    /// Resumes after a stop/hibernate are timed and recorded into `estimator`.
+    pub fn with_spawn_estimator(mut self, estimator: SpawnTimeEstimator) -> Self {
+        self.spawn_estimator = Some(estimator);
+        self
+    }
+
+    /// Save the spawn estimator to this file whenever a resume is recorded
+    ///
+    /// Load it back with `SpawnTimeEstimator::load` on the next start.
+    pub fn with_spawn_history(mut self, path: impl Into<PathBuf>) -> Self {
+        self.spawn_history = Some(path.into());
+        self
+    }
+
+    /// See `DrainManager::with_max_probe_failures`
+    pub fn with_max_probe_failures(mut self, max_failures: u32) -> Self {
+        self.max_probe_failures = max_failures;
+        self
+    }
+
+    /// Stop this container after draining
+    pub fn with_container_name(mut self, name: impl Into<String>) -> Self {
+        self.container_name = Some(name.into());
+        self
+    }
+
+    /// Record stop/hibernate suspensions in this file so the next start resumes
+    pub fn with_resume_marker(mut self, path: impl Into<PathBuf>) -> Self {
+        self.resume_marker = Some(path.into());
+        self
+    }
+
+    /// Read the boot ID from `path` instead of `BOOT_ID_PATH` (e.g. in tests)
+    pub fn with_boot_id_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.boot_id_path = path.into();
+        self
+    }
+
+    /// Drain budget for a notice
+    ///
+    /// Time until action minus the stop buffer and the estimated spawn time
+    /// (if any), capped by the drain timeout; see `DrainManager::effective_budget`.
+    pub fn drain_budget(&self, notice: &SpotInterruptionNotice) -> Duration {
+        let estimated_spawn_secs = self
+            .spawn_estimator
+            .as_ref()
+            .map_or(0.0, SpawnTimeEstimator::estimated_spawn_secs);
+        DrainManager::with_timeout(self.drain_timeout.unwrap_or(Duration::MAX)).effective_budget(
+            notice.seconds_until_action,
+            estimated_spawn_secs,
+            Duration::from_secs(STOP_BUFFER_SECS),
+        )
+    }
+
+    /// Run the full shutdown sequence for a termination notice
+    pub async fn handle(&self, notice: &SpotInterruptionNotice) -> Result<DrainResult> {
+        info!(
+            instance_id = %self.instance_id,
+            action = ?notice.action,
+            seconds_until_action = notice.seconds_until_action,
+            "Handling spot interruption notice"
+        );
+
+        self.report(NodeStatusUpdate::new(&self.instance_id, NodeState::Draining))
+            .await;
+
+        let budget = self.drain_budget(notice);
+        let deadline = Instant::now() + budget;
+        let mut drain_manager = DrainManager::with_timeout(budget)
+            .with_max_probe_failures(self.max_probe_failures);
+        if let Some(ref gate) = self.drain_gate {
+            drain_manager = drain_manager
+                .with_reject_new(true)
+                .with_drain_gate(gate.clone());
+        }
+
+        let drain = match drain_manager
+            .drain_until(&self.instance_id, &self.vllm_client, deadline)
+            .await
+        {
+            Ok(drain) => drain,
+            Err(e) => {
+                if let Some(ref metrics) = self.metrics {
+                    metrics.record_failover(false);
+                }
+                return Err(e);
+            }
+        };
+        if let Some(ref metrics) = self.metrics {
+            metrics.record_drain_failover(&drain);
+        }
+
+        let state = if notice.action.terminates_instance() {
+            if let Some(ref name) = self.container_name {
+                self.stop_container(name).await;
+            }
+            NodeState::Drained
+        } else {
+            // The instance comes back; the container stops (or hibernates) with it
+            self.write_resume_marker().await;
+            NodeState::Suspended
+        };
+
+        self.report(NodeStatusUpdate::new(&self.instance_id, state).with_drain(drain.clone()))
+            .await;
+
+        Ok(drain)
+    }
+
+    /// Resume serving if the last run ended in a stop/hibernate suspension
+    ///
+    /// Call at startup with the pending spot notice, if any. Reopens the
+    /// drain gate, restarts the container (if configured) and reports
+    /// `Resumed`. With a spawn estimator, also waits for vLLM to become
+    /// healthy and records how long the resume took. Returns whether a
+    /// resume happened.
+    ///
+    /// An agent restarted before the suspension took effect finds the marker
+    /// too: if the boot ID hasn't changed and a notice is still pending, the
+    /// marker is kept and the gate stays closed instead.
+    pub async fn resume_if_suspended(
+        &mut self,
+        pending: Option<&SpotInterruptionNotice>,
+    ) -> Result<bool> {
+        let Some(ref marker) = self.resume_marker else {
+            return Ok(false);
+        };
+        if !tokio::fs::try_exists(marker).await? {
+            return Ok(false);
+        }
+
+        let suspended_boot_id = tokio::fs::read_to_string(marker).await?;
+        if let Some(notice) = pending
+            && self.boot_id().await.as_deref() == Some(suspended_boot_id.trim())
+        {
+            warn!(
+                action = ?notice.action,
+                "Suspension still pending on this boot; not resuming"
+            );
+            if let Some(ref gate) = self.drain_gate {
+                gate.start_draining();
+            }
+            return Ok(false);
+        }
+
+        info!(instance_id = %self.instance_id, "Resuming after spot stop/hibernate");
+        tokio::fs::remove_file(marker).await?;
+        let resume_started = Instant::now();
+
+        if let Some(ref gate) = self.drain_gate {
+            gate.reopen();
+        }
+        if let Some(ref name) = self.container_name {
+            self.start_container(name).await;
+        }
+
+        self.report(NodeStatusUpdate::new(&self.instance_id, NodeState::Resumed))
+            .await;
+
+        if self.spawn_estimator.is_some() {
+            self.record_resume_time(resume_started).await;
+        }
+
+        Ok(true)
+    }
+
+    /// Wait for vLLM to become healthy and record the resume as a spawn sample
+    async fn record_resume_time(&mut self, resume_started: Instant) {
+        while !self.vllm_client.health_check().await.unwrap_or(false) {
+            if resume_started.elapsed() >= RESUME_READY_TIMEOUT {
+                warn!(
+                    "vLLM not healthy {:?} after resuming; not recording the spawn time",
+                    RESUME_READY_TIMEOUT
+                );
+                return;
+            }
+            tokio::time::sleep(RESUME_POLL_INTERVAL).await;
+        }
+
+        let spawn_time = resume_started.elapsed();
+        info!(spawn_time_secs = spawn_time.as_secs_f64(), "vLLM healthy after resume");
+        let Some(ref mut estimator) = self.spawn_estimator else {
+            return;
+        };
+        estimator.record(spawn_time);
+        if let Some(ref path) = self.spawn_history
+            && let Err(e) = estimator.save(path)
+        {
+            warn!(error = %e, path = %path.display(), "Failed to save spawn history");
+        }
+    }
+
+    /// Current boot ID, or `None` if it can't be read
+    async fn boot_id(&self) -> Option<String> {
+        match tokio::fs::read_to_string(&self.boot_id_path).await {
+            Ok(id) => Some(id.trim().to_string()),
+            Err(e) => {
+                warn!(error = %e, path = %self.boot_id_path.display(), "Failed to read boot ID");
+                None
+            }
+        }
+    }
+
+    /// Write the resume marker with the current boot ID, logging (not
+    /// failing) on error
+    async fn write_resume_marker(&self) {
+        let Some(ref marker) = self.resume_marker else {
+            return;
+        };
+        if let Some(parent) = marker.parent() {
+            let _ = tokio::fs::create_dir_all(parent).await;
+        }
+        let boot_id = self.boot_id().await.unwrap_or_default();
+        if let Err(e) = tokio::fs::write(marker, boot_id).await {
+            warn!(error = %e, path = %marker.display(), "Failed to write resume marker");
+        }
+    }
+
+    /// Post a status update, logging (not failing) on error
+    async fn report(&self, update: NodeStatusUpdate) {
+        if let Some(ref fleet) = self.fleet
+            && let Err(e) = fleet.report_status(&update).await
+        {
+            warn!(error = %e, state = ?update.state, "Failed to report status to fleet API");
+        }
+    }
+
+    async fn start_container(&self, name: &str) {
+        info!(container = %name, "Starting vLLM container");
+        match self.executor.run("docker", &to_args(&["start", name])).await {
+            Ok(output) if output.status.success() => info!("vLLM container started"),
+            Ok(output) => warn!(
+                "Failed to start container: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ),
+            Err(e) => warn!("Failed to run docker start: {}", e),
+        }
+    }
+
+    async fn stop_container(&self, name: &str) {
+        info!(container = %name, "Stopping vLLM container");
+        let stop_timeout = STOP_BUFFER_SECS.to_string();
+        match self
+            .executor
+            .run("docker", &to_args(&["stop", "-t", &stop_timeout, name]))
+            .await
+        {
+            Ok(output) if output.status.success() => info!("vLLM container stopped"),
+            Ok(output) => warn!(
+                "Failed to stop container: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ),
+            Err(e) => warn!("Failed to run docker stop: {}", e),
+        }
+    }
+}
+
+/// Runs the shutdown sequence for the first notice, then stops monitoring
+///
+/// The instance is going away (or being suspended), so there is nothing left
+/// to monitor for.
+impl MonitorHandler for TerminationHandler {
+    async fn on_notice(&mut self, notice: &SpotInterruptionNotice) -> ControlFlow<()> {
+        match self.handle(notice).await {
+            Ok(result) => info!(
+                status = ?result.status,
+                drain_time_secs = result.drain_time_secs,
+                "Shutdown complete"
+            ),
+            Err(e) => error!(error = %e, "Shutdown sequence failed"),
+        }
+        ControlFlow::Break(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::MockExecutor;
+    use crate::drain::{DEFAULT_ESTIMATED_SPAWN_SECS, DrainStatus};
+    use crate::monitor::SpotAction;
+    use chrono::Utc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn notice(seconds_until_action: u64) -> SpotInterruptionNotice {
+        SpotInterruptionNotice {
+            action: SpotAction::Terminate,
+            time: Utc::now(),
+            seconds_until_action,
+        }
+    }
+
+    #[test]
+    fn test_drain_budget() {
+        let handler = TerminationHandler::new("i-test", VllmClient::new("http://127.0.0.1:1"));
+        assert_eq!(handler.drain_budget(&notice(120)).as_secs(), 115);
+        assert_eq!(handler.drain_budget(&notice(3)).as_secs(), 0);
+
+        let handler = handler.with_drain_timeout(Duration::from_secs(60));
+        assert_eq!(handler.drain_budget(&notice(120)).as_secs(), 60);
+
+        // Room is left for the replacement to come up
+        let mut spawns = SpawnTimeEstimator::new();
+        spawns.record(Duration::from_secs(20));
+        let handler = handler.with_spawn_estimator(spawns);
+        assert_eq!(handler.drain_budget(&notice(120)).as_secs(), 60);
+        assert_eq!(handler.drain_budget(&notice(30)).as_secs(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_handle_termination_reports_to_fleet() {
+        let vllm = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/metrics"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "vllm:num_requests_running 0\nvllm:num_requests_waiting 0\n",
+            ))
+            .mount(&vllm)
+            .await;
+
+        let fleet = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/nodes/i-test/status"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&fleet)
+            .await;
+
+        let metrics = AgentMetrics::new().unwrap();
+        let docker = MockExecutor::new();
+        let handler = TerminationHandler::new("i-test", VllmClient::new(vllm.uri()))
+            .with_fleet(FleetClient::new(fleet.uri()))
+            .with_metrics(metrics.clone())
+            .with_container_name("vllm")
+            .with_executor(docker.clone());
+        let result = handler.handle(&notice(120)).await.unwrap();
+        assert_eq!(result.status, DrainStatus::Drained);
+        assert_eq!(docker.calls(), ["docker stop -t 5 vllm"]);
+        assert!(
+            metrics
+                .render()
+                .unwrap()
+                .contains("synkti_failovers_total{result=\"success\"} 1")
+        );
+
+        let updates: Vec<NodeStatusUpdate> = fleet
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| serde_json::from_slice(&r.body).unwrap())
+            .collect();
+        assert_eq!(updates[0].state, NodeState::Draining);
+        assert_eq!(updates[1].state, NodeState::Drained);
+        assert_eq!(updates[1].drain.as_ref().unwrap().status, DrainStatus::Drained);
+    }
+
+    #[tokio::test]
+    async fn test_stop_notice_suspends_and_resumes() {
+        let fleet = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/nodes/i-test/status"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&fleet)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("synkti-resume-{}", std::process::id()));
+        let marker = dir.join("suspended");
+        let boot_id = dir.join("boot_id");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&boot_id, "boot-1\n").unwrap();
+        let gate = DrainGate::new();
+        let docker = MockExecutor::new();
+        let mut handler = TerminationHandler::new("i-test", VllmClient::new("http://127.0.0.1:1"))
+            .with_fleet(FleetClient::new(fleet.uri()))
+            .with_drain_gate(gate.clone())
+            .with_resume_marker(&marker)
+            .with_boot_id_path(&boot_id)
+            .with_max_probe_failures(1)
+            .with_container_name("vllm")
+            .with_executor(docker.clone());
+
+        assert!(!handler.resume_if_suspended(None).await.unwrap());
+
+        let stop = SpotInterruptionNotice {
+            action: SpotAction::Stop,
+            ..notice(10)
+        };
+        handler.handle(&stop).await.unwrap();
+        assert!(gate.is_draining());
+        assert_eq!(std::fs::read_to_string(&marker).unwrap(), "boot-1");
+        assert!(docker.calls().is_empty(), "container is left running on stop");
+
+        // Rebooted: IMDS may still show the old action, but the boot ID moved on
+        std::fs::write(&boot_id, "boot-2\n").unwrap();
+        assert!(handler.resume_if_suspended(Some(&stop)).await.unwrap());
+        assert!(!gate.is_draining());
+        assert!(!marker.exists());
+        assert_eq!(docker.calls(), ["docker start vllm"]);
+
+        let states: Vec<NodeState> = fleet
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| serde_json::from_slice::<NodeStatusUpdate>(&r.body).unwrap().state)
+            .collect();
+        assert_eq!(
+            states,
+            [NodeState::Draining, NodeState::Suspended, NodeState::Resumed]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restart_before_suspension_does_not_resume() {
+        let dir = std::env::temp_dir().join(format!("synkti-false-resume-{}", std::process::id()));
+        let marker = dir.join("suspended");
+        let boot_id = dir.join("boot_id");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&boot_id, "boot-1\n").unwrap();
+        std::fs::write(&marker, "boot-1").unwrap();
+
+        // The agent restarted while the stop notice is still pending
+        let gate = DrainGate::new();
+        let docker = MockExecutor::new();
+        let mut handler = TerminationHandler::new("i-test", VllmClient::new("http://127.0.0.1:1"))
+            .with_drain_gate(gate.clone())
+            .with_resume_marker(&marker)
+            .with_boot_id_path(&boot_id)
+            .with_container_name("vllm")
+            .with_executor(docker.clone());
+        let stop = SpotInterruptionNotice {
+            action: SpotAction::Stop,
+            ..notice(60)
+        };
+
+        assert!(!handler.resume_if_suspended(Some(&stop)).await.unwrap());
+        assert!(marker.exists());
+        assert!(gate.is_draining());
+        assert!(docker.calls().is_empty());
+
+        // Same boot, but the action was cancelled: the instance never went down
+        assert!(handler.resume_if_suspended(None).await.unwrap());
+        assert!(!marker.exists());
+        assert!(!gate.is_draining());
+        assert_eq!(docker.calls(), ["docker start vllm"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resume_records_spawn_time() {
+        let vllm = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&vllm)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("synkti-resume-spawn-{}", std::process::id()));
+        let marker = dir.join("suspended");
+        let history = dir.join("spawn-times.json");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&marker, "i-test").unwrap();
+
+        let mut handler = TerminationHandler::new("i-test", VllmClient::new(vllm.uri()))
+            .with_resume_marker(&marker)
+            .with_spawn_estimator(SpawnTimeEstimator::new())
+            .with_spawn_history(&history)
+            .with_container_name("vllm")
+            .with_executor(MockExecutor::new());
+
+        assert!(handler.resume_if_suspended(None).await.unwrap());
+
+        // One quick sample replaces the default estimate, here and on disk
+        let saved = SpawnTimeEstimator::load(&history).unwrap();
+        assert!(saved.estimated_spawn_secs() < DEFAULT_ESTIMATED_SPAWN_SECS);
+        // The default estimate would leave 120 - 5 - 60 seconds
+        assert!(handler.drain_budget(&notice(120)) > Duration::from_secs(55));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_stops_after_first_notice() {
+        let docker = MockExecutor::new();
+        let mut handler = TerminationHandler::new("i-test", VllmClient::new("http://127.0.0.1:1"))
+            .with_max_probe_failures(1)
+            .with_container_name("vllm")
+            .with_executor(docker.clone());
+
+        let notices = futures::stream::iter([notice(10), notice(5)]);
+        let stopped_on = crate::monitor::dispatch_notices(notices, &mut handler).await;
+        assert_eq!(stopped_on.unwrap().seconds_until_action, 10);
+        assert_eq!(docker.calls(), ["docker stop -t 5 vllm"]);
+    }
+
+    #[tokio::test]
+    async fn test_handle_termination_without_fleet() {
+        let handler = TerminationHandler::new("i-test", VllmClient::new("http://127.0.0.1:1"))
+            .with_max_probe_failures(1);
+        // Unreachable vLLM is assumed gone rather than drained
+        let result = handler.handle(&notice(10)).await.unwrap();
+        assert_eq!(result.status, DrainStatus::Completed);
+    }
+}
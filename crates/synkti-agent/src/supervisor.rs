@@ -0,0 +1,251 @@
+//! Crash supervision for the vLLM container
+//!
+//! Spot preemption is handled by the monitor and drain path, but a container
+//! can also die on its own (OOM, CUDA errors). The supervisor polls the
+//! container and restarts it on unexpected exit, backing off between restarts
+//! and giving up after a fixed budget so a crash loop doesn't spin forever.
+//! The budget is restored once the container stays up, so occasional crashes
+//! over a long uptime never add up to giving up.
+
+use crate::error::{AgentError as OrchestratorError, Result};
+use std::future::Future;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Log lines captured from a crashed container
+pub const CRASH_LOG_TAIL: u32 = 50;
+
+/// Upper bound on the delay between restarts
+pub const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Consecutive healthy checks after which the restart budget is restored
+/// (5 minutes at a 5 second check interval)
+pub const STABLE_CHECKS: u32 = 60;
+
+/// Container operations the supervisor needs
+pub trait SupervisedContainer {
+    /// Whether the container is currently running
+    fn is_running(&self) -> impl Future<Output = bool>;
+
+    /// (Re)start the container, returning its ID
+    fn start(&mut self) -> impl Future<Output = Result<String>>;
+
+    /// Recent container logs
+    fn logs(&self, tail: Option<u32>) -> impl Future<Output = Result<String>>;
+}
+
+/// Emitted each time the supervisor restarts the container
+#[derive(Debug, Clone)]
+pub struct RestartEvent {
+    /// Restart number (1-based)
+    pub attempt: u32,
+    /// Tail of the crashed container's logs, if they could be read
+    pub crash_logs: Option<String>,
+    /// New container ID, or the restart error
+    pub result: std::result::Result<String, String>,
+}
+
+/// Delay before the given restart: `check_interval * 2^(attempt-1)`, capped
+pub fn restart_backoff(check_interval: Duration, attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    check_interval.saturating_mul(factor).min(MAX_RESTART_BACKOFF)
+}
+
+/// Supervise a container, restarting it whenever it stops
+///
+/// Runs until the restart budget is exhausted, then returns an error. The
+/// budget (and the backoff) resets after `STABLE_CHECKS` consecutive checks
+/// find the container running, so only restarts in a crash loop count. Callers
+/// stop supervision by dropping the future (e.g. when a spot notice arrives
+/// and the container is being stopped on purpose).
+pub async fn supervise<C: SupervisedContainer>(
+    container: &mut C,
+    check_interval: Duration,
+    max_restarts: u32,
+    mut on_restart: impl FnMut(&RestartEvent),
+) -> Result<()> {
+    let mut restarts = 0;
+    let mut healthy_checks = 0;
+
+    loop {
+        tokio::time::sleep(check_interval).await;
+
+        if container.is_running().await {
+            healthy_checks += 1;
+            if restarts > 0 && healthy_checks >= STABLE_CHECKS {
+                info!(restarts, "vLLM container stable again, restoring restart budget");
+                restarts = 0;
+            }
+            continue;
+        }
+        healthy_checks = 0;
+
+        if restarts >= max_restarts {
+            error!(restarts, "vLLM container keeps crashing, giving up");
+            return Err(OrchestratorError::Docker(format!(
+                "Container crashed after {} restarts, not restarting again",
+                restarts
+            )));
+        }
+        restarts += 1;
+
+        let crash_logs = container.logs(Some(CRASH_LOG_TAIL)).await.ok();
+        if let Some(ref logs) = crash_logs {
+            warn!("vLLM container exited unexpectedly, last logs:\n{}", logs);
+        } else {
+            warn!("vLLM container exited unexpectedly (logs unavailable)");
+        }
+
+        let backoff = restart_backoff(check_interval, restarts);
+        info!(
+            attempt = restarts,
+            max_restarts,
+            backoff_ms = backoff.as_millis() as u64,
+            "Restarting vLLM container"
+        );
+        tokio::time::sleep(backoff).await;
+
+        let result = container.start().await.map_err(|e| e.to_string());
+        if let Err(ref e) = result {
+            warn!(attempt = restarts, "vLLM restart failed: {}", e);
+        }
+
+        on_restart(&RestartEvent {
+            attempt: restarts,
+            crash_logs,
+            result,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::collections::VecDeque;
+
+    /// Reports running states from a script, then stays in the last state
+    struct ScriptedContainer {
+        states: VecDeque<bool>,
+        last: bool,
+        starts: u32,
+    }
+
+    impl ScriptedContainer {
+        fn new(states: &[bool]) -> Self {
+            Self {
+                states: states.iter().copied().collect(),
+                last: *states.last().unwrap(),
+                starts: 0,
+            }
+        }
+    }
+
+    impl SupervisedContainer for ScriptedContainer {
+        async fn is_running(&self) -> bool {
+            self.states.front().copied().unwrap_or(self.last)
+        }
+
+        async fn start(&mut self) -> Result<String> {
+            self.starts += 1;
+            self.states.pop_front();
+            Ok(format!("container-{}", self.starts))
+        }
+
+        async fn logs(&self, _tail: Option<u32>) -> Result<String> {
+            Ok("CUDA out of memory".to_string())
+        }
+    }
+
+    /// Stays up for `STABLE_CHECKS` checks after each start, then crashes;
+    /// stays up for good after `crashes` crashes
+    struct OccasionallyCrashing {
+        crashes: u32,
+        checks_since_start: Cell<u32>,
+        starts: u32,
+    }
+
+    impl SupervisedContainer for OccasionallyCrashing {
+        async fn is_running(&self) -> bool {
+            let checks = self.checks_since_start.get();
+            self.checks_since_start.set(checks + 1);
+            self.starts >= self.crashes || checks < STABLE_CHECKS
+        }
+
+        async fn start(&mut self) -> Result<String> {
+            self.starts += 1;
+            self.checks_since_start.set(0);
+            Ok(format!("container-{}", self.starts))
+        }
+
+        async fn logs(&self, _tail: Option<u32>) -> Result<String> {
+            Ok(String::new())
+        }
+    }
+
+    #[test]
+    fn test_restart_backoff() {
+        let interval = Duration::from_secs(5);
+        assert_eq!(restart_backoff(interval, 1), Duration::from_secs(5));
+        assert_eq!(restart_backoff(interval, 3), Duration::from_secs(20));
+        assert_eq!(restart_backoff(interval, 10), MAX_RESTART_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn test_restarts_dead_container_once() {
+        // Dead on first check, alive after restart
+        let mut container = ScriptedContainer::new(&[false, true]);
+        let mut events = Vec::new();
+
+        let supervised = supervise(&mut container, Duration::from_millis(5), 3, |e| {
+            events.push(e.clone())
+        });
+        // Still supervising (container healthy) when the timeout fires
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), supervised)
+                .await
+                .is_err()
+        );
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].attempt, 1);
+        assert_eq!(events[0].crash_logs.as_deref(), Some("CUDA out of memory"));
+        assert_eq!(events[0].result, Ok("container-1".to_string()));
+        assert_eq!(container.starts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_restarts() {
+        let mut container = ScriptedContainer::new(&[false]);
+        let mut restarts = 0;
+
+        let result = supervise(&mut container, Duration::from_millis(1), 2, |_| restarts += 1).await;
+
+        assert!(result.is_err());
+        assert_eq!(restarts, 2);
+        assert_eq!(container.starts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_budget_restored_after_stable_period() {
+        let mut container = OccasionallyCrashing {
+            crashes: 3,
+            checks_since_start: Cell::new(0),
+            starts: 0,
+        };
+        let mut attempts = Vec::new();
+
+        // One restart allowed, but each crash follows a stable period
+        let supervised = supervise(&mut container, Duration::from_millis(1), 1, |e| {
+            attempts.push(e.attempt)
+        });
+        assert!(
+            tokio::time::timeout(Duration::from_secs(2), supervised)
+                .await
+                .is_err()
+        );
+
+        assert_eq!(attempts, [1, 1, 1]);
+        assert_eq!(container.starts, 3);
+    }
+}
@@ -0,0 +1,185 @@
+//! Graceful model swap
+//!
+//! Changes the served model in place: drain in-flight requests, stop the old
+//! container, start a new one with the new config. Cheaper than a full
+//! failover when the node itself is healthy. If the new config sets a
+//! `warmup_prompt`, the swap only succeeds once a tiny completion works
+//! (`VllmContainer::start` warms up every container it starts).
+
+use crate::drain::{DrainManager, DrainStatus};
+use crate::error::Result;
+use crate::vllm::{VllmClient, VllmConfig};
+use std::future::Future;
+use std::time::Instant;
+use tracing::info;
+
+/// Container operations a model swap needs
+pub trait SwappableContainer {
+    /// Stop the running container
+    fn stop(&mut self) -> impl Future<Output = Result<()>>;
+
+    /// Replace the config used by the next `start`
+    fn set_config(&mut self, config: VllmConfig);
+
+    /// Start the container and wait until it serves, returning its ID
+    fn start(&mut self) -> impl Future<Output = Result<String>>;
+}
+
+/// Phase timings of a model swap (seconds)
+#[derive(Debug, Clone)]
+pub struct ModelSwapTimes {
+    /// Outcome of the drain phase
+    pub drain_status: DrainStatus,
+    /// Time spent draining in-flight requests
+    pub drain_secs: f64,
+    /// Time spent stopping the old container
+    pub stop_secs: f64,
+    /// Time until the new container was healthy (and warmed up, if configured)
+    pub start_secs: f64,
+    /// End-to-end swap time
+    pub total_secs: f64,
+    /// ID of the new container
+    pub container_id: String,
+}
+
+/// Drain, stop, reconfigure and restart a container with `new_config`
+pub async fn swap_model<C: SwappableContainer>(
+    container: &mut C,
+    new_config: VllmConfig,
+    drain_manager: &DrainManager,
+    vllm_client: &VllmClient,
+    instance_id: &str,
+) -> Result<ModelSwapTimes> {
+    let start = Instant::now();
+    info!(model = %new_config.model, "Swapping served model");
+
+    let drain = drain_manager.drain(instance_id, vllm_client).await?;
+    let drain_secs = start.elapsed().as_secs_f64();
+
+    let phase = Instant::now();
+    container.stop().await?;
+    let stop_secs = phase.elapsed().as_secs_f64();
+
+    container.set_config(new_config);
+
+    let phase = Instant::now();
+    let container_id = container.start().await?;
+    let start_secs = phase.elapsed().as_secs_f64();
+
+    let times = ModelSwapTimes {
+        drain_status: drain.status,
+        drain_secs,
+        stop_secs,
+        start_secs,
+        total_secs: start.elapsed().as_secs_f64(),
+        container_id,
+    };
+    info!(
+        total_secs = times.total_secs,
+        drain_secs = times.drain_secs,
+        start_secs = times.start_secs,
+        "Model swap completed"
+    );
+    Ok(times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// Records lifecycle calls, noting whether vLLM had been drained first
+    struct RecordingContainer<'a> {
+        vllm: &'a MockServer,
+        calls: Vec<String>,
+        /// Error returned by `start`, if set
+        start_error: Option<&'static str>,
+    }
+
+    impl SwappableContainer for RecordingContainer<'_> {
+        async fn stop(&mut self) -> Result<()> {
+            let drained = !self.vllm.received_requests().await.unwrap().is_empty();
+            self.calls.push(format!("stop(drained={})", drained));
+            Ok(())
+        }
+
+        fn set_config(&mut self, config: VllmConfig) {
+            self.calls.push(format!("set_config({})", config.model));
+        }
+
+        async fn start(&mut self) -> Result<String> {
+            self.calls.push("start".to_string());
+            match self.start_error {
+                Some(message) => Err(crate::error::AgentError::HealthCheck(message.to_string())),
+                None => Ok("new-container".to_string()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_swap_model_ordering() {
+        let vllm = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/metrics"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "vllm:num_requests_running 0\nvllm:num_requests_waiting 0\n",
+            ))
+            .mount(&vllm)
+            .await;
+
+        let mut container = RecordingContainer {
+            vllm: &vllm,
+            calls: Vec::new(),
+            start_error: None,
+        };
+        let times = swap_model(
+            &mut container,
+            VllmConfig::new("Qwen/Qwen2.5-7B"),
+            &DrainManager::new(),
+            &VllmClient::new(vllm.uri()),
+            "i-test",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            container.calls,
+            vec!["stop(drained=true)", "set_config(Qwen/Qwen2.5-7B)", "start"]
+        );
+        assert_eq!(times.drain_status, DrainStatus::Drained);
+        assert_eq!(times.container_id, "new-container");
+        assert!(times.total_secs >= times.drain_secs + times.stop_secs + times.start_secs);
+    }
+
+    #[tokio::test]
+    async fn test_swap_fails_when_warmup_fails() {
+        let vllm = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/metrics"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "vllm:num_requests_running 0\nvllm:num_requests_waiting 0\n",
+            ))
+            .mount(&vllm)
+            .await;
+
+        // The new container came up but failed its warmup completion
+        let mut container = RecordingContainer {
+            vllm: &vllm,
+            calls: Vec::new(),
+            start_error: Some("Warmup completion failed: status 500"),
+        };
+        let err = swap_model(
+            &mut container,
+            VllmConfig::new("bad/model-path").with_warmup_prompt("Hello"),
+            &DrainManager::new(),
+            &VllmClient::new(vllm.uri()),
+            "i-test",
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.to_string().contains("Warmup completion failed"));
+        assert_eq!(container.calls.last().unwrap(), "start");
+    }
+}
@@ -2,10 +2,12 @@
 //!
 //! Manages vLLM Docker containers for ML inference.
 
-use crate::error::{AgentError as OrchestratorError, Result};
+use crate::command::{CommandExecutor, SystemExecutor, to_args};
+use crate::error::{AgentError as OrchestratorError, DockerFailure, Result};
+use crate::drain::DrainManager;
+use crate::supervisor::{self, RestartEvent, SupervisedContainer};
+use crate::swap::{self, ModelSwapTimes, SwappableContainer};
 use serde::{Deserialize, Serialize};
-use std::process::Command;
-use tokio::process::Command as AsyncCommand;
 use tracing::{debug, error, info, warn};
 
 /// vLLM configuration
@@ -31,7 +33,7 @@ pub struct VllmConfig {
     pub tensor_parallel_size: usize,
 
     /// Quantization format (awq, gptq, etc.)
-    pub quantization: Option<String>,
+    pub quantization: Option<Quantization>,
 
     /// GPU memory utilization (fraction, 0.0-1.0)
     #[serde(default = "default_gpu_memory_utilization")]
@@ -43,6 +45,151 @@ pub struct VllmConfig {
 
     /// Container name
     pub container_name: Option<String>,
+
+    /// Scheduler preset (None keeps vLLM's own defaults)
+    #[serde(default)]
+    pub workload_profile: Option<WorkloadProfile>,
+
+    /// Additional vLLM flags, appended last so they override presets
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+
+    /// Prompt sent as a tiny completion after startup to prove inference works
+    #[serde(default)]
+    pub warmup_prompt: Option<String>,
+
+    /// Container shared memory (`--shm-size`); Docker's 64MB default breaks
+    /// NCCL and tensor-parallel workers
+    #[serde(default = "default_shm_size")]
+    pub shm_size: String,
+
+    /// CPU limit (`--cpus`), unlimited if unset
+    #[serde(default)]
+    pub cpu_limit: Option<f64>,
+
+    /// Memory limit (`--memory`, e.g. `64g`), unlimited if unset
+    #[serde(default)]
+    pub memory_limit: Option<String>,
+
+    /// Content digest of `image` (`sha256:...`); pins the exact image run
+    #[serde(default)]
+    pub image_digest: Option<String>,
+}
+
+/// Scheduler presets trading per-request latency against batch throughput
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkloadProfile {
+    /// Few concurrent sequences and a small token budget per step, so decode
+    /// steps aren't stalled behind long prefills
+    LatencyOptimized,
+    /// Large batches and token budget for maximum tokens/s
+    ThroughputOptimized,
+    /// Middle ground between the two
+    Balanced,
+}
+
+impl WorkloadProfile {
+    /// Config/CLI name of the profile
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LatencyOptimized => "latency_optimized",
+            Self::ThroughputOptimized => "throughput_optimized",
+            Self::Balanced => "balanced",
+        }
+    }
+
+    /// vLLM flags implementing this profile
+    pub fn vllm_args(&self) -> Vec<String> {
+        let (max_num_seqs, max_num_batched_tokens) = match self {
+            Self::LatencyOptimized => (32, 2048),
+            Self::ThroughputOptimized => (256, 16384),
+            Self::Balanced => (128, 8192),
+        };
+
+        vec![
+            "--max-num-seqs".to_string(),
+            max_num_seqs.to_string(),
+            "--max-num-batched-tokens".to_string(),
+            max_num_batched_tokens.to_string(),
+            "--enable-chunked-prefill".to_string(),
+        ]
+    }
+}
+
+impl std::fmt::Display for WorkloadProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for WorkloadProfile {
+    type Err = OrchestratorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().replace('-', "_").as_str() {
+            "latency_optimized" | "latency" => Ok(Self::LatencyOptimized),
+            "throughput_optimized" | "throughput" => Ok(Self::ThroughputOptimized),
+            "balanced" => Ok(Self::Balanced),
+            _ => Err(OrchestratorError::Config(format!(
+                "Unknown workload profile '{}' (expected latency, throughput or balanced)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Weight quantization formats accepted by vLLM's `--quantization`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Quantization {
+    Awq,
+    Gptq,
+    Fp8,
+    Bitsandbytes,
+}
+
+impl Quantization {
+    /// Value passed to `--quantization`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Awq => "awq",
+            Self::Gptq => "gptq",
+            Self::Fp8 => "fp8",
+            Self::Bitsandbytes => "bitsandbytes",
+        }
+    }
+
+    /// Bytes per model weight
+    pub fn bytes_per_parameter(&self) -> f64 {
+        match self {
+            Self::Awq | Self::Gptq | Self::Bitsandbytes => 0.5,
+            Self::Fp8 => 1.0,
+        }
+    }
+}
+
+impl std::fmt::Display for Quantization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Quantization {
+    type Err = OrchestratorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "awq" => Ok(Self::Awq),
+            "gptq" => Ok(Self::Gptq),
+            "fp8" => Ok(Self::Fp8),
+            "bitsandbytes" => Ok(Self::Bitsandbytes),
+            _ => Err(OrchestratorError::Config(format!(
+                "Unknown quantization format '{}' (expected awq, gptq, fp8 or bitsandbytes)",
+                s
+            ))),
+        }
+    }
 }
 
 fn default_vllm_image() -> String {
@@ -69,6 +216,10 @@ fn default_host() -> String {
     "0.0.0.0".to_string()
 }
 
+fn default_shm_size() -> String {
+    "16g".to_string()
+}
+
 impl Default for VllmConfig {
     fn default() -> Self {
         Self {
@@ -81,6 +232,13 @@ impl Default for VllmConfig {
             gpu_memory_utilization: default_gpu_memory_utilization(),
             host: default_host(),
             container_name: None,
+            workload_profile: None,
+            extra_args: Vec::new(),
+            warmup_prompt: None,
+            shm_size: default_shm_size(),
+            cpu_limit: None,
+            memory_limit: None,
+            image_digest: None,
         }
     }
 }
@@ -94,12 +252,74 @@ impl VllmConfig {
         }
     }
 
+    /// Config for one of several workloads on the same instance
+    ///
+    /// Takes the next port from `ports` and names the container after it
+    /// (`synkti-vllm-<port>`), so the `-p` mapping, health URL and name agree.
+    pub fn colocated(model: impl Into<String>, ports: &mut PortAllocator) -> Result<Self> {
+        let port = ports.allocate()?;
+        Ok(Self::new(model)
+            .with_port(port)
+            .with_container_name(format!("{}-{}", CONTAINER_NAME_PREFIX, port)))
+    }
+
+    /// Load a config from a JSON file
+    ///
+    /// Only `model` is required; other fields fall back to their defaults.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            OrchestratorError::Config(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        serde_json::from_str(&contents).map_err(|e| {
+            OrchestratorError::Config(format!("Invalid vLLM config {}: {}", path.display(), e))
+        })
+    }
+
     /// Set Docker image
+    ///
+    /// Clears any pinned digest, which belonged to the previous image.
     pub fn with_image(mut self, image: impl Into<String>) -> Self {
         self.image = image.into();
+        self.image_digest = None;
+        self
+    }
+
+    /// Pin the image to a content digest (`sha256:<hex>`, or just the hex)
+    ///
+    /// Tags like `latest` move; a digest guarantees every failover runs the
+    /// same image. See `VllmContainer::resolve_digest` to pin what a tag
+    /// currently points at.
+    pub fn with_image_digest(mut self, digest: impl Into<String>) -> Self {
+        let digest = digest.into();
+        self.image_digest = Some(if digest.starts_with("sha256:") {
+            digest
+        } else {
+            format!("sha256:{}", digest)
+        });
         self
     }
 
+    /// Image reference passed to `docker run`
+    ///
+    /// `repository@sha256:...` when a digest is pinned (the tag is dropped),
+    /// otherwise `image` as configured.
+    pub fn image_ref(&self) -> String {
+        match self.image_digest {
+            Some(ref digest) => format!("{}@{}", image_repository(&self.image), digest),
+            None => self.image.clone(),
+        }
+    }
+
+    /// Whether the image is neither digest-pinned nor on a fixed tag
+    pub fn is_unpinned(&self) -> bool {
+        if self.image_digest.is_some() || self.image.contains('@') {
+            return false;
+        }
+        let repository = image_repository(&self.image);
+        repository == self.image || self.image.ends_with(":latest")
+    }
+
     /// Set port
     pub fn with_port(mut self, port: u16) -> Self {
         self.port = port;
@@ -118,10 +338,46 @@ impl VllmConfig {
         self
     }
 
-    /// Set quantization
-    pub fn with_quantization(mut self, quantization: impl Into<String>) -> Self {
-        self.quantization = Some(quantization.into());
-        self
+    /// Set quantization, rejecting formats vLLM doesn't know
+    pub fn with_quantization(mut self, quantization: &str) -> Result<Self> {
+        self.quantization = Some(quantization.parse()?);
+        Ok(self)
+    }
+
+    /// Warning if a served model's context length differs from `max_model_len`
+    ///
+    /// vLLM silently caps the context at what the model supports, so a config
+    /// asking for more than that serves less than expected.
+    pub fn context_length_warning(&self, served: &ModelInfo) -> Option<String> {
+        match served.max_model_len {
+            Some(len) if len != self.max_model_len => Some(format!(
+                "{} is served with max_model_len={} but the config requests {}",
+                served.id, len, self.max_model_len
+            )),
+            _ => None,
+        }
+    }
+
+    /// Warnings for settings vLLM is likely to reject at startup
+    pub fn compatibility_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let tp = self.tensor_parallel_size;
+
+        match self.quantization {
+            Some(Quantization::Bitsandbytes) if tp > 1 => warnings.push(format!(
+                "bitsandbytes quantization does not support tensor parallelism (tensor_parallel_size={})",
+                tp
+            )),
+            Some(q @ (Quantization::Awq | Quantization::Gptq)) if tp > 1 && !tp.is_power_of_two() => {
+                warnings.push(format!(
+                    "{} weights are packed in power-of-two groups; tensor_parallel_size={} will likely fail to shard",
+                    q, tp
+                ))
+            }
+            _ => {}
+        }
+
+        warnings
     }
 
     /// Set container name
@@ -130,6 +386,56 @@ impl VllmConfig {
         self
     }
 
+    /// Set the scheduler preset
+    pub fn with_workload_profile(mut self, profile: WorkloadProfile) -> Self {
+        self.workload_profile = Some(profile);
+        self
+    }
+
+    /// Append raw vLLM flags (e.g. `--enforce-eager`)
+    pub fn with_extra_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.extra_args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Validate a (re)started server with a tiny completion of `prompt`
+    ///
+    /// A server can pass `/health` and still fail real inference (e.g. a bad
+    /// model path or tokenizer); the warmup catches that before traffic does.
+    /// `VllmContainer::start` runs it on every start, including supervisor
+    /// restarts and model swaps, and stops the container if it fails.
+    pub fn with_warmup_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.warmup_prompt = Some(prompt.into());
+        self
+    }
+
+    /// Set the container's shared memory size (e.g. `32g`)
+    pub fn with_shm_size(mut self, size: impl Into<String>) -> Self {
+        self.shm_size = size.into();
+        self
+    }
+
+    /// Limit the container to `cpus` CPUs
+    pub fn with_cpu_limit(mut self, cpus: f64) -> Self {
+        self.cpu_limit = Some(cpus);
+        self
+    }
+
+    /// Limit the container's memory (e.g. `64g`)
+    pub fn with_memory_limit(mut self, limit: impl Into<String>) -> Self {
+        self.memory_limit = Some(limit.into());
+        self
+    }
+
+    /// Estimated GPU memory for this model and context length (MB)
+    pub fn estimated_memory_mb(&self) -> f64 {
+        estimate_model_memory_mb(&self.model, self.max_model_len, self.quantization)
+    }
+
     /// Check if GPU is available on this system
     fn has_gpu() -> bool {
         // Check for nvidia-smi or GPU devices
@@ -138,8 +444,32 @@ impl VllmConfig {
             || std::path::Path::new("/usr/local/bin/nvidia-smi").exists()
     }
 
-    /// Build Docker run arguments
-    fn docker_run_args(&self) -> Vec<String> {
+    /// Render the `docker run` invocation as a single shell-safe command line
+    ///
+    /// For remote execution (e.g. a generated user-data or SSM script) where
+    /// the command goes through a shell; every argument, including
+    /// `extra_args`, is quoted so it can't break out of the invocation.
+    /// `gpu` is whether the target host has a GPU, which is usually not the
+    /// host rendering the script.
+    pub fn docker_run_command_line(&self, gpu: bool) -> String {
+        std::iter::once("docker".to_string())
+            .chain(self.docker_run_args(gpu).iter().map(|arg| shell_quote(arg)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Build Docker run arguments, using the NVIDIA runtime when `gpu` is set
+    fn docker_run_args(&self, gpu: bool) -> Vec<String> {
+        for warning in self.compatibility_warnings() {
+            tracing::warn!("⚠️  {}", warning);
+        }
+        if self.is_unpinned() {
+            tracing::warn!(
+                "⚠️  Image {} is unpinned and may change between failovers; pin it with a digest",
+                self.image
+            );
+        }
+
         let mut args = vec![
             "run".to_string(),
             "-d".to_string(),
@@ -153,7 +483,7 @@ impl VllmConfig {
         args.push(format!("{}:{}", self.model, self.model));
 
         // Use nvidia runtime for GPU support (more compatible than --gpus all)
-        if Self::has_gpu() {
+        if gpu {
             // Try to use nvidia runtime first, fall back to --gpus all
             args.push("--runtime".to_string());
             args.push("nvidia".to_string());
@@ -164,12 +494,23 @@ impl VllmConfig {
             tracing::warn!("⚠️  No GPU detected, running in CPU mode (vLLM will be slow or may not work)");
         }
 
+        args.push("--shm-size".to_string());
+        args.push(self.shm_size.clone());
+        if let Some(cpus) = self.cpu_limit {
+            args.push("--cpus".to_string());
+            args.push(cpus.to_string());
+        }
+        if let Some(ref memory) = self.memory_limit {
+            args.push("--memory".to_string());
+            args.push(memory.clone());
+        }
+
         if let Some(ref name) = self.container_name {
             args.push("--name".to_string());
             args.push(name.clone());
         }
 
-        args.push(self.image.clone());
+        args.push(self.image_ref());
         args.push("--model".to_string());
         args.push(self.model.clone());
         args.push("--port".to_string());
@@ -184,15 +525,243 @@ impl VllmConfig {
             args.push(self.tensor_parallel_size.to_string());
         }
 
-        if let Some(ref quant) = self.quantization {
+        if let Some(quant) = self.quantization {
             args.push("--quantization".to_string());
-            args.push(quant.clone());
+            args.push(quant.to_string());
+        }
+
+        if let Some(profile) = self.workload_profile {
+            args.extend(profile.vllm_args());
         }
 
+        args.extend(self.extra_args.iter().cloned());
+
         args
     }
 }
 
+/// Container name prefix for workloads co-located on one instance
+pub const CONTAINER_NAME_PREFIX: &str = "synkti-vllm";
+
+/// Hands out distinct, currently free host ports for co-located vLLM servers
+///
+/// Ports already handed out are never reused, even if nothing has bound
+/// them yet, so two workloads configured back-to-back can't collide.
+#[derive(Debug, Clone)]
+pub struct PortAllocator {
+    next: u16,
+    allocated: std::collections::BTreeSet<u16>,
+}
+
+impl PortAllocator {
+    /// Allocate ports upward from `base`, which must not be 0
+    ///
+    /// Binding port 0 asks the OS for any free port, so it can't be handed
+    /// out as a fixed container port.
+    pub fn new(base: u16) -> Result<Self> {
+        if base == 0 {
+            return Err(OrchestratorError::Config(
+                "Port allocator base port must be at least 1".to_string(),
+            ));
+        }
+        Ok(Self {
+            next: base,
+            allocated: std::collections::BTreeSet::new(),
+        })
+    }
+
+    /// Next port that is free on this host and not yet allocated
+    pub fn allocate(&mut self) -> Result<u16> {
+        for port in self.next..=u16::MAX {
+            if !self.allocated.contains(&port)
+                && std::net::TcpListener::bind(("0.0.0.0", port)).is_ok()
+            {
+                self.allocated.insert(port);
+                self.next = port.saturating_add(1);
+                return Ok(port);
+            }
+        }
+        Err(OrchestratorError::Config(format!(
+            "No free port at or above {}",
+            self.next
+        )))
+    }
+
+    /// Return a port for reuse
+    pub fn release(&mut self, port: u16) {
+        self.allocated.remove(&port);
+        self.next = self.next.min(port);
+    }
+}
+
+/// A model served by vLLM, as listed at `/v1/models`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// Model ID (HuggingFace model ID or served model name)
+    pub id: String,
+    /// Context length the server was started with
+    #[serde(default)]
+    pub max_model_len: Option<usize>,
+    /// Owner reported by the server (vLLM reports "vllm")
+    #[serde(default)]
+    pub owned_by: Option<String>,
+    /// Unix timestamp the model was loaded
+    #[serde(default)]
+    pub created: Option<i64>,
+}
+
+/// A request count read from vLLM, which may not be available
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestCount {
+    Known(u32),
+    /// No source exported the count; callers should assume requests are in flight
+    Unknown,
+}
+
+impl RequestCount {
+    /// The count, if known
+    pub fn known(self) -> Option<u32> {
+        match self {
+            Self::Known(count) => Some(count),
+            Self::Unknown => None,
+        }
+    }
+}
+
+/// Error for a container that never became ready
+///
+/// Classified from the container's log tail and exit code when recognisable,
+/// so a CUDA or OOM crash during model load gets the matching remediation.
+fn readiness_failure(log_tail: &str, exit_code: Option<i32>) -> OrchestratorError {
+    let mut detail = "vLLM did not become ready within 10 minutes".to_string();
+    if let Some(code) = exit_code.filter(|&code| code != 0) {
+        detail.push_str(&format!(" (container exited with code {})", code));
+    }
+    match DockerFailure::classify(log_tail, exit_code) {
+        Some(kind) => OrchestratorError::DockerFailed { kind, detail },
+        None => OrchestratorError::Docker(detail),
+    }
+}
+
+/// Value of the first sample of any of `names` in Prometheus text output
+fn find_metric_value(metrics: &str, names: &[&str]) -> Option<u32> {
+    metrics
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| names.iter().any(|name| line.starts_with(name)))
+        .find_map(|line| line.split_whitespace().last()?.parse::<f64>().ok())
+        .map(|value| value as u32)
+}
+
+/// Sum of every sample of any of `names` in Prometheus text output
+///
+/// Counters such as `vllm:request_success_total` are split by label (one
+/// series per finish reason), so all series are added up.
+fn sum_metric_values(metrics: &str, names: &[&str]) -> Option<f64> {
+    metrics
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| {
+            names.iter().any(|name| {
+                line.strip_prefix(name)
+                    .is_some_and(|rest| rest.starts_with('{') || rest.starts_with(' '))
+            })
+        })
+        .filter_map(|line| line.split_whitespace().last()?.parse::<f64>().ok())
+        .reduce(|total, value| total + value)
+}
+
+/// Image reference without its tag or digest
+///
+/// A colon only starts a tag after the last `/`, so registry ports
+/// (`registry:5000/vllm`) are kept.
+fn image_repository(image: &str) -> &str {
+    let image = image.split('@').next().unwrap_or(image);
+    let name_start = image.rfind('/').map_or(0, |i| i + 1);
+    match image[name_start..].find(':') {
+        Some(colon) => &image[..name_start + colon],
+        None => image,
+    }
+}
+
+/// Quote `arg` for POSIX `sh`
+///
+/// Arguments made only of safe characters are returned as-is; anything else
+/// is wrapped in single quotes, with embedded single quotes written as `'\''`.
+pub fn shell_quote(arg: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./:=,@%+".contains(c);
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Memory assumed for models whose size can't be parsed from the name (MB)
+pub const DEFAULT_MODEL_MEMORY_MB: f64 = 16_000.0;
+
+/// KV cache per token for a 7B fp16 model (32 layers x 4096 hidden x K/V x 2 bytes)
+const KV_MB_PER_TOKEN_7B: f64 = 0.5;
+
+/// Runtime overhead on top of weights + KV cache (CUDA context, activations)
+const MEMORY_OVERHEAD_FACTOR: f64 = 1.1;
+
+/// Parse the parameter count (in billions) from a HuggingFace model id
+///
+/// Recognises name segments like `7b`, `70B`, `0.5B`, `125m` and mixture-of-experts
+/// forms like `8x7B`. Returns `None` if no size segment is found.
+pub fn parse_parameter_count_billions(model_id: &str) -> Option<f64> {
+    let name = model_id.rsplit('/').next().unwrap_or(model_id);
+
+    // Split on separators but keep decimal points inside sizes (e.g. "0.5B")
+    name.split(['-', '_'])
+        .filter_map(|segment| {
+            let lower = segment.to_ascii_lowercase();
+            let (number, scale) = if let Some(n) = lower.strip_suffix('b') {
+                (n, 1.0)
+            } else if let Some(n) = lower.strip_suffix('m') {
+                (n, 0.001)
+            } else {
+                return None;
+            };
+
+            let count = match number.split_once('x') {
+                Some((experts, size)) => experts.parse::<f64>().ok()? * size.parse::<f64>().ok()?,
+                None => number.parse::<f64>().ok()?,
+            };
+            (count > 0.0).then_some(count * scale)
+        })
+        .next()
+}
+
+/// Estimate GPU memory needed to serve a model (MB)
+///
+/// Rough sizing for scheduling, not an exact figure: weights are parameter
+/// count x bytes per weight, and the KV cache for one `max_model_len` sequence
+/// is scaled from a 7B model by the square root of the size ratio (hidden size
+/// and layer count both grow with model size). Unparseable names fall back to
+/// `DEFAULT_MODEL_MEMORY_MB`.
+pub fn estimate_model_memory_mb(
+    model_id: &str,
+    max_model_len: usize,
+    quantization: Option<Quantization>,
+) -> f64 {
+    let Some(params_b) = parse_parameter_count_billions(model_id) else {
+        return DEFAULT_MODEL_MEMORY_MB;
+    };
+
+    // Unquantized weights are served in fp16
+    let bytes_per_parameter = quantization.map_or(2.0, |q| q.bytes_per_parameter());
+    let weights_mb = params_b * 1e9 * bytes_per_parameter / (1024.0 * 1024.0);
+    let kv_cache_mb = max_model_len as f64 * KV_MB_PER_TOKEN_7B * (params_b / 7.0).sqrt();
+
+    (weights_mb + kv_cache_mb) * MEMORY_OVERHEAD_FACTOR
+}
+
+/// Tokens generated by the post-start warmup completion
+pub const WARMUP_MAX_TOKENS: u32 = 4;
+
 /// vLLM container manager
 pub struct VllmContainer {
     /// vLLM configuration
@@ -200,6 +769,12 @@ pub struct VllmContainer {
 
     /// Container ID (if running)
     container_id: Option<String>,
+
+    /// Runs docker commands
+    executor: Box<dyn CommandExecutor>,
+
+    /// Whether this host has a GPU (detected at construction)
+    gpu: bool,
 }
 
 impl VllmContainer {
@@ -208,15 +783,70 @@ impl VllmContainer {
         Self {
             config,
             container_id: None,
+            executor: Box::new(SystemExecutor),
+            gpu: VllmConfig::has_gpu(),
+        }
+    }
+
+    /// Run docker commands through `executor` (e.g. a `MockExecutor` in tests)
+    pub fn with_executor(mut self, executor: impl CommandExecutor + 'static) -> Self {
+        self.executor = Box::new(executor);
+        self
+    }
+
+    /// Override GPU detection, e.g. to pin it in tests
+    pub fn with_gpu(mut self, gpu: bool) -> Self {
+        self.gpu = gpu;
+        self
+    }
+
+    async fn docker(&self, args: &[String]) -> std::io::Result<std::process::Output> {
+        self.executor.run("docker", args).await
+    }
+
+    /// Check that Docker is installed before running anything
+    ///
+    /// On GPU hosts, also warns if the NVIDIA container runtime isn't
+    /// registered, since `docker run --runtime nvidia` would then fail.
+    pub async fn check_docker_available(&self) -> Result<()> {
+        let output = match self.docker(&to_args(&["--version"])).await {
+            Ok(output) => output,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(OrchestratorError::DockerNotInstalled);
+            }
+            Err(e) => {
+                return Err(OrchestratorError::Docker(format!("Failed to run docker: {}", e)));
+            }
+        };
+        if !output.status.success() {
+            return Err(OrchestratorError::Docker(format!(
+                "docker --version failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        debug!("{}", String::from_utf8_lossy(&output.stdout).trim());
+
+        if self.gpu {
+            let runtimes = self
+                .docker(&to_args(&["info", "--format", "{{json .Runtimes}}"]))
+                .await
+                .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+                .unwrap_or_default();
+            if !runtimes.contains("nvidia") {
+                warn!("⚠️  GPU detected but Docker has no nvidia runtime; install nvidia-container-toolkit or vLLM will fail to start");
+            }
         }
+        Ok(())
     }
 
     /// Start the vLLM container
     pub async fn start(&mut self) -> Result<String> {
         info!("🤖 Starting vLLM container for model {}", self.config.model);
 
+        self.check_docker_available().await?;
+
         // Cold start timestamp tracking
-        let _ = std::fs::write("/tmp/cold-start-vllm.log", &format!("timestamp={} phase=vllm_start\n", chrono::Utc::now().timestamp()));
+        let _ = std::fs::write("/tmp/cold-start-vllm.log", format!("timestamp={} phase=vllm_start\n", chrono::Utc::now().timestamp()));
 
         // Verify model directory exists before starting container
         if std::path::Path::new(&self.config.model).exists() {
@@ -234,28 +864,24 @@ impl VllmContainer {
         // Remove any existing container with the same name (crash recovery)
         if let Some(ref name) = self.config.container_name {
             info!("Checking for existing container named '{}'...", name);
-            let _ = AsyncCommand::new("docker")
-                .args(["rm", "-f", name])
-                .output()
-                .await;
+            let _ = self.docker(&to_args(&["rm", "-f", name])).await;
         }
 
-        let args = self.config.docker_run_args();
+        let args = self.config.docker_run_args(self.gpu);
 
         info!("Docker run command: docker {}", args.join(" "));
 
-        let output = AsyncCommand::new("docker")
-            .args(&args)
-            .output()
+        let output = self
+            .docker(&args)
             .await
             .map_err(|e| OrchestratorError::Docker(format!("Failed to start vLLM: {}", e)))?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(OrchestratorError::Docker(format!(
-                "vLLM container failed to start: {}",
-                stderr
-            )));
+            return Err(OrchestratorError::from_docker_output(
+                "vLLM container failed to start",
+                &String::from_utf8_lossy(&output.stderr),
+                output.status.code(),
+            ));
         }
 
         let container_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
@@ -263,7 +889,7 @@ impl VllmContainer {
 
         // Cold start timestamp: container started
         let _ = std::fs::write("/tmp/cold-start-vllm.log",
-            &format!("timestamp={} phase=vllm_container_started container_id={}\n",
+            format!("timestamp={} phase=vllm_container_started container_id={}\n",
                     chrono::Utc::now().timestamp(), container_id));
 
         info!("vLLM container started: {}", container_id);
@@ -271,9 +897,30 @@ impl VllmContainer {
         // Wait for vLLM to be ready
         self.wait_for_ready().await?;
 
+        // Passing /health doesn't prove the model serves (e.g. a bad model
+        // path); don't hand back a container that fails its warmup
+        if let Some(ref prompt) = self.config.warmup_prompt
+            && let Err(e) = self.warmup(prompt).await
+        {
+            if let Err(stop_err) = self.stop().await {
+                warn!("Failed to stop container after warmup failure: {}", stop_err);
+            }
+            return Err(e);
+        }
+
         Ok(container_id)
     }
 
+    /// Send a tiny completion through the container's API
+    async fn warmup(&self, prompt: &str) -> Result<()> {
+        info!("Sending warmup completion");
+        VllmClient::new(format!("http://{}:{}", self.config.host, self.config.port))
+            .completion(prompt, WARMUP_MAX_TOKENS)
+            .await
+            .map(|_| ())
+            .map_err(|e| OrchestratorError::HealthCheck(format!("Warmup completion failed: {}", e)))
+    }
+
     /// Wait for vLLM API to be ready
     async fn wait_for_ready(&self) -> Result<()> {
         let client = reqwest::Client::new();
@@ -291,7 +938,7 @@ impl VllmContainer {
 
                     // Cold start timestamp: vLLM ready!
                     let _ = std::fs::write("/tmp/cold-start-vllm-ready.log",
-                        &format!("timestamp={} phase=vllm_health_ok\n", chrono::Utc::now().timestamp()));
+                        format!("timestamp={} phase=vllm_health_ok\n", chrono::Utc::now().timestamp()));
 
                     // Also append to main cold start log
                     let _ = std::fs::OpenOptions::new()
@@ -324,13 +971,12 @@ impl VllmContainer {
         error!("   Container ID: {:?}", self.container_id);
 
         // Try to get container logs for diagnosis
+        let mut log_tail = String::new();
+        let mut exit_code = None;
         if let Some(ref container_id) = self.container_id {
             error!("📜 Fetching container logs for diagnosis...");
 
-            let logs_output = tokio::process::Command::new("docker")
-                .args(["logs", "--tail", "50", container_id])
-                .output()
-                .await;
+            let logs_output = self.docker(&to_args(&["logs", "--tail", "50", container_id])).await;
 
             if let Ok(output) = logs_output {
                 let logs = String::from_utf8_lossy(&output.stdout);
@@ -346,12 +992,12 @@ impl VllmContainer {
                     }
                 }
                 error!("--- End of container logs ---");
+                log_tail = format!("{}\n{}", logs, stderr);
             }
 
             // Check container status
-            let inspect_output = tokio::process::Command::new("docker")
-                .args(["inspect", "--format={{.State.Status}}", container_id])
-                .output()
+            let inspect_output = self
+                .docker(&to_args(&["inspect", "--format={{.State.Status}}", container_id]))
                 .await;
 
             if let Ok(output) = inspect_output {
@@ -359,10 +1005,15 @@ impl VllmContainer {
                 error!("Container status: {}", status.trim());
             }
 
+            exit_code = self
+                .docker(&to_args(&["inspect", "-f", "{{.State.ExitCode}}", container_id]))
+                .await
+                .ok()
+                .and_then(|output| String::from_utf8_lossy(&output.stdout).trim().parse().ok());
+
             // Check if GPU is accessible
-            let gpu_output = tokio::process::Command::new("docker")
-                .args(["exec", container_id, "nvidia-smi", "-L"])
-                .output()
+            let gpu_output = self
+                .docker(&to_args(&["exec", container_id, "nvidia-smi", "-L"]))
                 .await;
 
             match gpu_output {
@@ -380,9 +1031,8 @@ impl VllmContainer {
             }
 
             // Check if model files are visible in container
-            let model_output = tokio::process::Command::new("docker")
-                .args(["exec", container_id, "ls", "-la", &self.config.model])
-                .output()
+            let model_output = self
+                .docker(&to_args(&["exec", container_id, "ls", "-la", &self.config.model]))
                 .await;
 
             if let Ok(output) = model_output {
@@ -400,9 +1050,7 @@ impl VllmContainer {
             }
         }
 
-        Err(OrchestratorError::Docker(
-            "vLLM did not become ready within 10 minutes".to_string(),
-        ))
+        Err(readiness_failure(&log_tail, exit_code))
     }
 
     /// Stop the vLLM container
@@ -410,9 +1058,8 @@ impl VllmContainer {
         if let Some(ref container_id) = self.container_id {
             info!("Stopping vLLM container {}", container_id);
 
-            let output = AsyncCommand::new("docker")
-                .args(["stop", container_id])
-                .output()
+            let output = self
+                .docker(&to_args(&["stop", container_id]))
                 .await
                 .map_err(|e| OrchestratorError::Docker(format!("Failed to stop container: {}", e)))?;
 
@@ -435,6 +1082,62 @@ impl VllmContainer {
         self.container_id.as_deref()
     }
 
+    /// Pull the configured image ahead of time
+    ///
+    /// A multi-GB pull doesn't fit in a failover grace period; pulling on a
+    /// warm node means `start` finds the image cached.
+    pub async fn pull_image(&self) -> Result<()> {
+        let image = self.config.image_ref();
+        info!("Pre-pulling image {}", image);
+        let output = self
+            .docker(&to_args(&["pull", "--quiet", &image]))
+            .await
+            .map_err(|e| OrchestratorError::Docker(format!("Failed to pull {}: {}", image, e)))?;
+        if !output.status.success() {
+            return Err(OrchestratorError::Docker(format!(
+                "Failed to pull {}: {}",
+                image,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Pin the configured image to the digest its tag currently points at
+    ///
+    /// Inspects the local image, pulling it first if it isn't present, and
+    /// stores the digest in the config so later starts run the same image.
+    pub async fn resolve_digest(&mut self) -> Result<String> {
+        let image = self.config.image.clone();
+        let inspect = to_args(&["image", "inspect", "--format", "{{index .RepoDigests 0}}", &image]);
+
+        let mut output = self.docker(&inspect).await?;
+        if !output.status.success() {
+            info!("Image {} not present locally, pulling to resolve its digest", image);
+            let pull = self.docker(&to_args(&["pull", &image])).await?;
+            if !pull.status.success() {
+                return Err(OrchestratorError::Docker(format!(
+                    "Failed to pull {}: {}",
+                    image,
+                    String::from_utf8_lossy(&pull.stderr).trim()
+                )));
+            }
+            output = self.docker(&inspect).await?;
+        }
+
+        let repo_digest = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let digest = repo_digest
+            .split_once('@')
+            .map(|(_, digest)| digest.to_string())
+            .ok_or_else(|| {
+                OrchestratorError::Docker(format!("No repository digest for image {}", image))
+            })?;
+
+        info!("Pinned {} to {}", image, digest);
+        self.config.image_digest = Some(digest.clone());
+        Ok(digest)
+    }
+
     /// Get vLLM API base URL
     pub fn api_url(&self) -> String {
         format!("http://{}:{}", self.config.host, self.config.port)
@@ -443,15 +1146,15 @@ impl VllmContainer {
     /// Check if container is running
     pub async fn is_running(&self) -> bool {
         if let Some(ref container_id) = self.container_id {
-            let output = Command::new("docker")
-                .args(["inspect", "-f", "{{.State.Running}}", container_id])
-                .output();
-
-            if let Ok(o) = output {
-                if o.status.success() {
-                    let stdout = String::from_utf8_lossy(&o.stdout);
-                    return stdout.trim() == "true";
-                }
+            let output = self
+                .docker(&to_args(&["inspect", "-f", "{{.State.Running}}", container_id]))
+                .await;
+
+            if let Ok(o) = output
+                && o.status.success()
+            {
+                let stdout = String::from_utf8_lossy(&o.stdout);
+                return stdout.trim() == "true";
             }
         }
         false
@@ -470,9 +1173,8 @@ impl VllmContainer {
             args.push(tail_lines.to_string());
         }
 
-        let output = AsyncCommand::new("docker")
-            .args(&args)
-            .output()
+        let output = self
+            .docker(&args)
             .await
             .map_err(|e| OrchestratorError::Docker(format!("Failed to get logs: {}", e)))?;
 
@@ -485,7 +1187,18 @@ impl VllmContainer {
     }
 
     /// Execute a checkpoint on the container
+    ///
+    /// Deprecated: Docker/CRIU checkpoints can't capture GPU state, so on GPU
+    /// hosts this returns `Unsupported` instead of attempting one. Use
+    /// stateless failover (drain and respawn) instead.
     pub async fn checkpoint(&self, checkpoint_id: &str) -> Result<()> {
+        warn!("⚠️  Container checkpointing is deprecated; use stateless failover instead");
+        if self.gpu {
+            return Err(OrchestratorError::Unsupported(
+                "GPU containers can't be checkpointed, use failover".to_string(),
+            ));
+        }
+
         let container_id = self
             .container_id
             .as_ref()
@@ -493,16 +1206,15 @@ impl VllmContainer {
 
         info!("Creating checkpoint {} for container {}", checkpoint_id, container_id);
 
-        let output = AsyncCommand::new("docker")
-            .args([
+        let output = self
+            .docker(&to_args(&[
                 "checkpoint",
                 "create",
                 "--checkpoint-dir=/tmp/checkpoints",
                 "--leave=true",
                 container_id,
                 checkpoint_id,
-            ])
-            .output()
+            ]))
             .await
             .map_err(|e| OrchestratorError::Docker(format!("Failed to create checkpoint: {}", e)))?;
 
@@ -517,13 +1229,81 @@ impl VllmContainer {
         info!("Checkpoint {} created successfully", checkpoint_id);
         Ok(())
     }
+
+    /// Restart the container whenever it exits unexpectedly
+    ///
+    /// Checks `is_running()` every `check_interval`; on exit, captures the
+    /// crash logs and restarts via `start()` with exponential backoff.
+    /// `on_restart` is called after every restart attempt. Returns an error
+    /// once `max_restarts` restarts have been used up without the container
+    /// staying up in between (see `supervisor::STABLE_CHECKS`).
+    pub async fn supervise(
+        &mut self,
+        check_interval: std::time::Duration,
+        max_restarts: u32,
+        on_restart: impl FnMut(&RestartEvent),
+    ) -> Result<()> {
+        supervisor::supervise(self, check_interval, max_restarts, on_restart).await
+    }
+
+    /// Serve a different model: drain, stop, and restart with `new_config`
+    ///
+    /// `vllm_client` must point at this container's API; `instance_id` is the
+    /// node being drained. See `swap::swap_model`.
+    pub async fn swap_model(
+        &mut self,
+        new_config: VllmConfig,
+        drain_manager: &DrainManager,
+        vllm_client: &VllmClient,
+        instance_id: &str,
+    ) -> Result<ModelSwapTimes> {
+        swap::swap_model(self, new_config, drain_manager, vllm_client, instance_id).await
+    }
+}
+
+impl SwappableContainer for VllmContainer {
+    async fn stop(&mut self) -> Result<()> {
+        VllmContainer::stop(self).await
+    }
+
+    fn set_config(&mut self, config: VllmConfig) {
+        self.config = config;
+    }
+
+    async fn start(&mut self) -> Result<String> {
+        VllmContainer::start(self).await
+    }
+}
+
+impl SupervisedContainer for VllmContainer {
+    async fn is_running(&self) -> bool {
+        VllmContainer::is_running(self).await
+    }
+
+    async fn start(&mut self) -> Result<String> {
+        VllmContainer::start(self).await
+    }
+
+    async fn logs(&self, tail: Option<u32>) -> Result<String> {
+        VllmContainer::logs(self, tail).await
+    }
 }
 
+/// Default health check path (vLLM and TGI both serve `/health`)
+pub const DEFAULT_HEALTH_PATH: &str = "/health";
+
 /// vLLM API client for health checks and queries
+#[derive(Clone)]
 pub struct VllmClient {
     /// Base URL for vLLM API
     base_url: String,
 
+    /// Path probed by `health_check`
+    health_path: String,
+
+    /// Status code `health_check` expects (any 2xx if unset)
+    expected_status: Option<u16>,
+
     /// HTTP client
     client: reqwest::Client,
 }
@@ -533,22 +1313,86 @@ impl VllmClient {
     pub fn new(base_url: impl Into<String>) -> Self {
         Self {
             base_url: base_url.into(),
+            health_path: DEFAULT_HEALTH_PATH.to_string(),
+            expected_status: None,
             client: reqwest::Client::new(),
         }
     }
 
+    /// Set the health check path (e.g. `/ready` behind a proxy)
+    pub fn with_health_path(mut self, path: impl Into<String>) -> Self {
+        let path = path.into();
+        self.health_path = if path.starts_with('/') {
+            path
+        } else {
+            format!("/{}", path)
+        };
+        self
+    }
+
+    /// Require an exact status code from the health endpoint
+    pub fn with_expected_status(mut self, code: u16) -> Self {
+        self.expected_status = Some(code);
+        self
+    }
+
+    fn health_url(&self) -> String {
+        format!("{}{}", self.base_url, self.health_path)
+    }
+
+    fn is_expected_status(&self, status: reqwest::StatusCode) -> bool {
+        match self.expected_status {
+            Some(code) => status.as_u16() == code,
+            None => status.is_success(),
+        }
+    }
+
     /// Check if vLLM is healthy
     pub async fn health_check(&self) -> Result<bool> {
-        let url = format!("{}/health", self.base_url);
+        match self.client.get(self.health_url()).send().await {
+            Ok(response) => Ok(self.is_expected_status(response.status())),
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Check a JSON readiness endpoint, e.g. `{"status": "ok"}`
+    ///
+    /// Probes the configured health path and returns true only if the response
+    /// passes the status check and `field` equals `expected`. Unreachable
+    /// servers and non-JSON bodies are reported as unhealthy.
+    pub async fn health_check_json(&self, field: &str, expected: &str) -> Result<bool> {
+        let response = match self.client.get(self.health_url()).send().await {
+            Ok(r) => r,
+            Err(_) => return Ok(false),
+        };
+
+        if !self.is_expected_status(response.status()) {
+            return Ok(false);
+        }
 
-        match self.client.get(&url).send().await {
-            Ok(response) => Ok(response.status().is_success()),
+        match response.json::<serde_json::Value>().await {
+            Ok(body) => Ok(body.get(field).and_then(|v| v.as_str()) == Some(expected)),
             Err(_) => Ok(false),
         }
     }
 
+    /// Get the health check path
+    pub fn health_path(&self) -> &str {
+        &self.health_path
+    }
+
     /// Get list of available models
     pub async fn list_models(&self) -> Result<Vec<String>> {
+        Ok(self
+            .list_models_detailed()
+            .await?
+            .into_iter()
+            .map(|m| m.id)
+            .collect())
+    }
+
+    /// Get available models with their metadata
+    pub async fn list_models_detailed(&self) -> Result<Vec<ModelInfo>> {
         let url = format!("{}/v1/models", self.base_url);
 
         let response = self
@@ -567,16 +1411,59 @@ impl VllmClient {
 
         #[derive(Deserialize)]
         struct ModelsResponse {
-            data: Vec<ModelData>,
+            data: Vec<ModelInfo>,
+        }
+
+        let models: ModelsResponse = response.json().await?;
+        Ok(models.data)
+    }
+
+    /// Generate a completion for `prompt` from the served model
+    ///
+    /// Uses the first model listed at `/v1/models` and returns the text of
+    /// the first choice.
+    pub async fn completion(&self, prompt: &str, max_tokens: u32) -> Result<String> {
+        let model = self.list_models().await?.into_iter().next().ok_or_else(|| {
+            OrchestratorError::HealthCheck("No model loaded for completion".to_string())
+        })?;
+        let url = format!("{}/v1/completions", self.base_url);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&serde_json::json!({
+                "model": model,
+                "prompt": prompt,
+                "max_tokens": max_tokens,
+            }))
+            .send()
+            .await
+            .map_err(OrchestratorError::Http)?;
+
+        if !response.status().is_success() {
+            return Err(OrchestratorError::HealthCheck(format!(
+                "Completion failed: status {}",
+                response.status()
+            )));
         }
 
         #[derive(Deserialize)]
-        struct ModelData {
-            id: String,
+        struct CompletionResponse {
+            choices: Vec<Choice>,
         }
 
-        let models: ModelsResponse = response.json().await?;
-        Ok(models.data.into_iter().map(|m| m.id).collect())
+        #[derive(Deserialize)]
+        struct Choice {
+            text: String,
+        }
+
+        let completion: CompletionResponse = response.json().await?;
+        completion
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.text)
+            .ok_or_else(|| OrchestratorError::HealthCheck("Completion returned no choices".to_string()))
     }
 
     /// Get raw Prometheus metrics from vLLM
@@ -604,76 +1491,112 @@ impl VllmClient {
 
     /// Get the number of currently running requests
     ///
-    /// Parses the `vllm:num_requests_running` metric from Prometheus output.
-    /// Returns 0 if the metric is not found or cannot be parsed.
-    pub async fn get_running_requests(&self) -> Result<u32> {
-        let metrics = match self.get_metrics().await {
-            Ok(m) => m,
-            Err(_) => return Ok(0), // Assume no requests if we can't get metrics
-        };
+    /// Reads the `vllm:num_requests_running` metric. Builds that don't export it
+    /// (older images, `--disable-log-stats`) fall back to `/load`, which counts
+    /// every request in the API server, queued ones included. If neither source
+    /// is available the count is `Unknown`, never 0: treating a missing metric as
+    /// idle would end drains early. Errors only if the server is unreachable.
+    pub async fn get_running_requests(&self) -> Result<RequestCount> {
+        self.get_request_counts().await.map(|(running, _)| running)
+    }
 
-        // Parse Prometheus format: vllm:num_requests_running{...} VALUE
-        // Also try: vllm_num_requests_running (older format)
-        for line in metrics.lines() {
-            let line = line.trim();
+    /// Get the running and waiting request counts together
+    ///
+    /// Both come from the `vllm:num_requests_*` metrics when exported; a
+    /// missing or unparseable waiting gauge is `Unknown`. Without the running
+    /// gauge, the `/load` count (which already includes queued requests) is
+    /// reported as running with 0 waiting. Errors only if the server is
+    /// unreachable.
+    pub async fn get_request_counts(&self) -> Result<(RequestCount, RequestCount)> {
+        let metrics = self.get_metrics().await;
+        if let Ok(ref metrics) = metrics
+            && let Some(running) = find_metric_value(
+                metrics,
+                &["vllm:num_requests_running", "vllm_num_requests_running"],
+            )
+        {
+            let waiting = find_metric_value(
+                metrics,
+                &["vllm:num_requests_waiting", "vllm_num_requests_waiting"],
+            );
+            return Ok((
+                RequestCount::Known(running),
+                waiting.map_or(RequestCount::Unknown, RequestCount::Known),
+            ));
+        }
 
-            // Skip comments and empty lines
-            if line.is_empty() || line.starts_with('#') {
-                continue;
+        match self.get_server_load().await {
+            Ok(load) => {
+                debug!(load, "num_requests_running unavailable, using /load");
+                Ok((RequestCount::Known(load), RequestCount::Known(0)))
             }
-
-            // Check for running requests metric
-            if line.starts_with("vllm:num_requests_running")
-                || line.starts_with("vllm_num_requests_running")
-            {
-                // Extract the value (last space-separated token)
-                if let Some(value_str) = line.split_whitespace().last() {
-                    if let Ok(value) = value_str.parse::<f64>() {
-                        return Ok(value as u32);
-                    }
+            Err(load_err) => match metrics {
+                Ok(_) => {
+                    warn!(
+                        error = %load_err,
+                        "No running-request count available (metric missing, /load failed)"
+                    );
+                    Ok((RequestCount::Unknown, RequestCount::Unknown))
                 }
-            }
+                Err(e) => Err(e),
+            },
         }
-
-        // Metric not found, assume no running requests
-        Ok(0)
     }
 
     /// Get the number of waiting requests (queue depth)
     ///
-    /// Parses the `vllm:num_requests_waiting` metric from Prometheus output.
-    pub async fn get_waiting_requests(&self) -> Result<u32> {
-        let metrics = match self.get_metrics().await {
-            Ok(m) => m,
-            Err(_) => return Ok(0),
-        };
+    /// Parses the `vllm:num_requests_waiting` metric from Prometheus output;
+    /// `Unknown` if the metric isn't exported.
+    pub async fn get_waiting_requests(&self) -> Result<RequestCount> {
+        let metrics = self.get_metrics().await?;
+        Ok(find_metric_value(
+            &metrics,
+            &["vllm:num_requests_waiting", "vllm_num_requests_waiting"],
+        )
+        .map_or(RequestCount::Unknown, RequestCount::Known))
+    }
 
-        for line in metrics.lines() {
-            let line = line.trim();
+    /// In-flight request count from vLLM's `/load` endpoint
+    ///
+    /// Only served when vLLM runs with `--enable-server-load-tracking`.
+    pub async fn get_server_load(&self) -> Result<u32> {
+        let url = format!("{}/load", self.base_url);
+        let response = self.client.get(&url).send().await?;
 
-            if line.is_empty() || line.starts_with('#') {
-                continue;
-            }
+        if !response.status().is_success() {
+            return Err(OrchestratorError::Other(format!(
+                "Failed to get server load: status {}",
+                response.status()
+            )));
+        }
 
-            if line.starts_with("vllm:num_requests_waiting")
-                || line.starts_with("vllm_num_requests_waiting")
-            {
-                if let Some(value_str) = line.split_whitespace().last() {
-                    if let Ok(value) = value_str.parse::<f64>() {
-                        return Ok(value as u32);
-                    }
-                }
-            }
+        #[derive(Deserialize)]
+        struct LoadResponse {
+            server_load: u32,
         }
 
-        Ok(0)
+        let load: LoadResponse = response.json().await?;
+        Ok(load.server_load)
     }
 
     /// Check if the server is idle (no running or waiting requests)
+    ///
+    /// An unknown running or waiting count is reported as not idle.
     pub async fn is_idle(&self) -> Result<bool> {
-        let running = self.get_running_requests().await?;
-        let waiting = self.get_waiting_requests().await?;
-        Ok(running == 0 && waiting == 0)
+        let counts = self.get_request_counts().await?;
+        Ok(counts == (RequestCount::Known(0), RequestCount::Known(0)))
+    }
+
+    /// Requests vLLM has finished since it started
+    ///
+    /// Reads the `vllm:request_success_total` counter, summed over finish
+    /// reasons; `None` if the server doesn't export it.
+    pub async fn get_completed_requests(&self) -> Result<Option<f64>> {
+        let metrics = self.get_metrics().await?;
+        Ok(sum_metric_values(
+            &metrics,
+            &["vllm:request_success_total", "vllm_request_success_total"],
+        ))
     }
 
     /// Get base URL
@@ -693,13 +1616,14 @@ mod tests {
             .with_max_model_len(8192)
             .with_tensor_parallel_size(2)
             .with_quantization("awq")
+            .unwrap()
             .with_container_name("vllm-test");
 
         assert_eq!(config.model, "meta-llama/Llama-2-7b-hf");
         assert_eq!(config.port, 8080);
         assert_eq!(config.max_model_len, 8192);
         assert_eq!(config.tensor_parallel_size, 2);
-        assert_eq!(config.quantization, Some("awq".to_string()));
+        assert_eq!(config.quantization, Some(Quantization::Awq));
         assert_eq!(config.container_name, Some("vllm-test".to_string()));
     }
 
@@ -711,13 +1635,702 @@ mod tests {
             port: 8000,
             max_model_len: 4096,
             tensor_parallel_size: 1,
-            quantization: Some("awq".to_string()),
+            quantization: Some(Quantization::Awq),
             gpu_memory_utilization: 0.9,
             host: "0.0.0.0".to_string(),
             container_name: Some("vllm-server".to_string()),
+            workload_profile: Some(WorkloadProfile::Balanced),
+            extra_args: vec!["--enforce-eager".to_string()],
+            warmup_prompt: Some("Hello".to_string()),
+            shm_size: "16g".to_string(),
+            cpu_limit: Some(8.0),
+            memory_limit: None,
+            image_digest: None,
         };
 
         let json = serde_json::to_string(&config).unwrap();
         let _parsed: VllmConfig = serde_json::from_str(&json).unwrap();
+        assert!(json.contains("\"quantization\":\"awq\""));
+        assert!(json.contains("\"workload_profile\":\"balanced\""));
+    }
+
+    #[test]
+    fn test_colocated_workloads_get_distinct_ports() {
+        // Hold the base port so the allocator has to skip it
+        let taken = std::net::TcpListener::bind("0.0.0.0:0").unwrap();
+        let base = taken.local_addr().unwrap().port();
+        let mut ports = PortAllocator::new(base).unwrap();
+
+        let a = VllmConfig::colocated("Qwen/Qwen2.5-0.5B", &mut ports).unwrap();
+        let b = VllmConfig::colocated("Qwen/Qwen2.5-7B", &mut ports).unwrap();
+
+        assert_ne!(a.port, base);
+        assert_ne!(a.port, b.port);
+        assert_ne!(a.container_name, b.container_name);
+        assert_eq!(a.container_name, Some(format!("synkti-vllm-{}", a.port)));
+        let args = a.docker_run_args(false);
+        assert!(args.contains(&format!("{0}:{0}", a.port)));
+
+        ports.release(a.port);
+        assert_eq!(ports.allocate().unwrap(), a.port);
+
+        assert!(PortAllocator::new(0).is_err());
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("--enforce-eager"), "--enforce-eager");
+        assert_eq!(shell_quote("meta-llama/Llama-2-7b-hf"), "meta-llama/Llama-2-7b-hf");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("two words"), "'two words'");
+        assert_eq!(shell_quote(r#"{"type":"dynamic"}"#), r#"'{"type":"dynamic"}'"#);
+        assert_eq!(shell_quote("$(reboot); rm -rf /"), "'$(reboot); rm -rf /'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn test_docker_run_command_line_quotes_extra_args() {
+        let config = VllmConfig::new("model").with_extra_args([
+            "--rope-scaling",
+            r#"{"rope_type":"dynamic","factor":2.0}"#,
+            "--served-model-name",
+            "x; touch /tmp/pwned",
+        ]);
+
+        let line = config.docker_run_command_line(false);
+        assert!(line.starts_with("docker run -d -p 8000:8000 "));
+        assert!(line.ends_with(
+            r#" --rope-scaling '{"rope_type":"dynamic","factor":2.0}' --served-model-name 'x; touch /tmp/pwned'"#
+        ));
+        assert!(!line.contains("--runtime"));
+
+        // Rendered for a GPU host regardless of where it is rendered
+        let line = config.docker_run_command_line(true);
+        assert!(line.contains(" --runtime nvidia --env PYTORCH_CUDA_ALLOC_CONF=expandable_segments:True "));
+    }
+
+    #[test]
+    fn test_resource_limit_args() {
+        let flag = |args: &[String], name: &str| {
+            args.iter()
+                .position(|a| a == name)
+                .map(|i| args[i + 1].clone())
+        };
+
+        let args = VllmConfig::new("model").docker_run_args(false);
+        assert_eq!(flag(&args, "--shm-size").as_deref(), Some("16g"));
+        assert_eq!(flag(&args, "--cpus"), None);
+        assert_eq!(flag(&args, "--memory"), None);
+
+        let config = VllmConfig::new("model")
+            .with_shm_size("32g")
+            .with_cpu_limit(7.5)
+            .with_memory_limit("60g");
+        let args = config.docker_run_args(false);
+        assert_eq!(flag(&args, "--shm-size").as_deref(), Some("32g"));
+        assert_eq!(flag(&args, "--cpus").as_deref(), Some("7.5"));
+        assert_eq!(flag(&args, "--memory").as_deref(), Some("60g"));
+        // Docker flags, so they come before the image
+        let image = args.iter().position(|a| a == &config.image).unwrap();
+        assert!(args.iter().position(|a| a == "--memory").unwrap() < image);
+        assert!(config.docker_run_command_line(false).contains(" --shm-size 32g --cpus 7.5 --memory 60g "));
+    }
+
+    #[test]
+    fn test_image_digest_pinning() {
+        let digest = "sha256:4b1f0c2e9a7d";
+        let config = VllmConfig::new("model").with_image_digest(digest);
+        assert_eq!(config.image_ref(), "vllm/vllm-openai@sha256:4b1f0c2e9a7d");
+        assert!(config.docker_run_args(false).contains(&config.image_ref()));
+        assert!(!config.is_unpinned());
+
+        // Bare hex gets the algorithm prefix; registry ports aren't mistaken for tags
+        let config = VllmConfig::new("model")
+            .with_image("registry:5000/vllm:v0.6.3")
+            .with_image_digest("4b1f0c2e9a7d");
+        assert_eq!(config.image_ref(), "registry:5000/vllm@sha256:4b1f0c2e9a7d");
+        // Changing the image drops the old digest
+        assert_eq!(config.with_image("vllm/vllm-openai:v0.6.3").image_ref(), "vllm/vllm-openai:v0.6.3");
+
+        assert!(VllmConfig::new("model").is_unpinned());
+        assert!(VllmConfig::new("model").with_image("vllm/vllm-openai").is_unpinned());
+        assert!(!VllmConfig::new("model").with_image("vllm/vllm-openai:v0.6.3").is_unpinned());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_digest_pulls_missing_image() {
+        use crate::command::MockExecutor;
+
+        let docker = MockExecutor::new();
+        docker.push_reply(false, "", "Error: No such image"); // inspect
+        docker.push_reply(true, "", ""); // pull
+        docker.push_reply(true, "vllm/vllm-openai@sha256:4b1f0c2e9a7d\n", ""); // inspect
+        let mut container = VllmContainer::new(VllmConfig::new("model")).with_executor(docker.clone());
+
+        assert_eq!(container.resolve_digest().await.unwrap(), "sha256:4b1f0c2e9a7d");
+        assert_eq!(container.config.image_ref(), "vllm/vllm-openai@sha256:4b1f0c2e9a7d");
+        assert_eq!(
+            docker.calls(),
+            [
+                "docker image inspect --format {{index .RepoDigests 0}} vllm/vllm-openai:latest",
+                "docker pull vllm/vllm-openai:latest",
+                "docker image inspect --format {{index .RepoDigests 0}} vllm/vllm-openai:latest",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pull_image_uses_pinned_reference() {
+        use crate::command::MockExecutor;
+
+        let docker = MockExecutor::new();
+        let config = VllmConfig::new("model").with_image_digest("sha256:4b1f0c2e9a7d");
+        let container = VllmContainer::new(config).with_executor(docker.clone());
+        container.pull_image().await.unwrap();
+
+        docker.push_reply(false, "", "manifest unknown");
+        assert!(container.pull_image().await.is_err());
+        assert_eq!(
+            docker.calls(),
+            ["docker pull --quiet vllm/vllm-openai@sha256:4b1f0c2e9a7d"; 2]
+        );
+    }
+
+    #[test]
+    fn test_workload_profile_args() {
+        let flags = |profile: WorkloadProfile| profile.vllm_args().join(" ");
+        assert_eq!(
+            flags(WorkloadProfile::LatencyOptimized),
+            "--max-num-seqs 32 --max-num-batched-tokens 2048 --enable-chunked-prefill"
+        );
+        assert_eq!(
+            flags(WorkloadProfile::ThroughputOptimized),
+            "--max-num-seqs 256 --max-num-batched-tokens 16384 --enable-chunked-prefill"
+        );
+        assert_eq!(
+            flags(WorkloadProfile::Balanced),
+            "--max-num-seqs 128 --max-num-batched-tokens 8192 --enable-chunked-prefill"
+        );
+        assert_eq!("latency".parse::<WorkloadProfile>().unwrap(), WorkloadProfile::LatencyOptimized);
+        assert_eq!(
+            "throughput-optimized".parse::<WorkloadProfile>().unwrap(),
+            WorkloadProfile::ThroughputOptimized
+        );
+        assert!("fast".parse::<WorkloadProfile>().is_err());
+
+        // No profile: vLLM defaults, no scheduler flags
+        let args = VllmConfig::new("model").docker_run_args(false);
+        assert!(!args.iter().any(|a| a == "--max-num-seqs"));
+
+        // Profile flags come after the model flags, extra args last
+        let args = VllmConfig::new("model")
+            .with_workload_profile(WorkloadProfile::LatencyOptimized)
+            .with_extra_args(["--max-num-seqs", "8"])
+            .docker_run_args(false);
+        let tail: Vec<&str> = args.iter().rev().take(7).rev().map(String::as_str).collect();
+        assert_eq!(
+            tail,
+            [
+                "--max-num-seqs", "32", "--max-num-batched-tokens", "2048",
+                "--enable-chunked-prefill", "--max-num-seqs", "8"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_vllm_config_from_file() {
+        let dir = std::env::temp_dir().join(format!("synkti-vllm-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("vllm.json");
+        let config = VllmConfig::new("meta-llama/Llama-2-13b-hf")
+            .with_port(8001)
+            .with_quantization("gptq")
+            .unwrap();
+        std::fs::write(&path, serde_json::to_string_pretty(&config).unwrap()).unwrap();
+        let loaded = VllmConfig::from_file(&path).unwrap();
+        assert_eq!(loaded.model, config.model);
+        assert_eq!(loaded.port, 8001);
+        assert_eq!(loaded.quantization, Some(Quantization::Gptq));
+
+        // Omitted fields take defaults
+        let minimal = dir.join("minimal.json");
+        std::fs::write(&minimal, r#"{"model": "Qwen/Qwen2.5-0.5B"}"#).unwrap();
+        let loaded = VllmConfig::from_file(&minimal).unwrap();
+        assert_eq!(loaded.port, default_port());
+        assert_eq!(loaded.max_model_len, default_max_model_len());
+
+        let err = VllmConfig::from_file(dir.join("missing.json")).unwrap_err();
+        assert!(err.to_string().contains("Failed to read"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_quantization_parsing() {
+        assert_eq!("awq".parse::<Quantization>().unwrap(), Quantization::Awq);
+        assert_eq!("GPTQ".parse::<Quantization>().unwrap(), Quantization::Gptq);
+        assert_eq!("fp8".parse::<Quantization>().unwrap(), Quantization::Fp8);
+        assert_eq!(
+            "bitsandbytes".parse::<Quantization>().unwrap(),
+            Quantization::Bitsandbytes
+        );
+
+        let err = VllmConfig::new("model").with_quantization("awk").unwrap_err();
+        assert!(err.to_string().contains("Unknown quantization format 'awk'"));
+
+        let json = r#"{"model": "m", "quantization": "int3"}"#;
+        assert!(serde_json::from_str::<VllmConfig>(json).is_err());
+    }
+
+    #[test]
+    fn test_quantization_compatibility_warnings() {
+        let config = VllmConfig::new("model").with_quantization("awq").unwrap();
+        assert!(config.compatibility_warnings().is_empty());
+        assert!(config.clone().with_tensor_parallel_size(4).compatibility_warnings().is_empty());
+        assert_eq!(config.with_tensor_parallel_size(3).compatibility_warnings().len(), 1);
+
+        let config = VllmConfig::new("model")
+            .with_quantization("bitsandbytes")
+            .unwrap()
+            .with_tensor_parallel_size(2);
+        assert_eq!(config.compatibility_warnings().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_parameter_count() {
+        assert_eq!(parse_parameter_count_billions("meta-llama/Llama-2-7b-hf"), Some(7.0));
+        assert_eq!(parse_parameter_count_billions("meta-llama/Llama-2-70B-chat"), Some(70.0));
+        assert_eq!(parse_parameter_count_billions("Qwen/Qwen2.5-0.5B-Instruct"), Some(0.5));
+        assert_eq!(parse_parameter_count_billions("mistralai/Mixtral-8x7B-v0.1"), Some(56.0));
+        assert_eq!(parse_parameter_count_billions("facebook/opt-125m"), Some(0.125));
+        assert_eq!(parse_parameter_count_billions("/models/my-model"), None);
+    }
+
+    #[test]
+    fn test_estimate_model_memory() {
+        // 7B fp16: ~13.0 GB weights + 2 GB KV cache at 4096 tokens
+        let fp16 = estimate_model_memory_mb("meta-llama/Llama-2-7b-hf", 4096, None);
+        assert!((16_000.0..17_500.0).contains(&fp16), "7B fp16 was {}", fp16);
+
+        // 7B awq: 4-bit weights are a quarter of fp16
+        let awq = estimate_model_memory_mb("TheBloke/Llama-2-7B-AWQ", 4096, Some(Quantization::Awq));
+        assert!((5_500.0..6_500.0).contains(&awq), "7B awq was {}", awq);
+
+        // 70B fp16 needs multiple GPUs
+        let large = estimate_model_memory_mb("meta-llama/Llama-2-70b-hf", 4096, None);
+        assert!(large > 140_000.0, "70B fp16 was {}", large);
+
+        assert_eq!(
+            estimate_model_memory_mb("/models/custom", 4096, None),
+            DEFAULT_MODEL_MEMORY_MB
+        );
+        assert_eq!(
+            VllmConfig::new("meta-llama/Llama-2-7b-hf").estimated_memory_mb(),
+            fp16
+        );
+    }
+
+    #[tokio::test]
+    async fn test_health_check_custom_path_and_status() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/ready"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&server)
+            .await;
+
+        let client = VllmClient::new(server.uri());
+        assert!(client.health_check().await.unwrap());
+
+        let client = VllmClient::new(server.uri()).with_health_path("ready");
+        assert_eq!(client.health_path(), "/ready");
+        assert!(client.health_check().await.unwrap());
+
+        let client = VllmClient::new(server.uri())
+            .with_health_path("/ready")
+            .with_expected_status(200);
+        assert!(!client.health_check().await.unwrap());
+
+        // Unmatched paths return 404
+        let client = VllmClient::new(server.uri()).with_health_path("/missing");
+        assert!(!client.health_check().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_container_docker_commands() {
+        use crate::command::MockExecutor;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Stands in for the vLLM API that start() waits on
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        let port = server.address().port();
+
+        let docker = MockExecutor::new();
+        docker.push_reply(true, "Docker version 27.3.1\n", ""); // --version
+        docker.push_reply(true, "", ""); // rm -f
+        docker.push_reply(true, "abc123\n", ""); // run
+        let mut config = VllmConfig::new("Qwen/Qwen2.5-0.5B")
+            .with_port(port)
+            .with_container_name("vllm-test");
+        config.host = "127.0.0.1".to_string();
+        let mut container = VllmContainer::new(config)
+            .with_executor(docker.clone())
+            .with_gpu(false);
+
+        assert_eq!(container.start().await.unwrap(), "abc123");
+        docker.push_reply(true, "true\n", "");
+        assert!(container.is_running().await);
+        container.logs(Some(20)).await.unwrap();
+        container.stop().await.unwrap();
+
+        let calls = docker.calls();
+        assert_eq!(calls[0], "docker --version");
+        assert_eq!(calls[1], "docker rm -f vllm-test");
+        assert!(calls[2].starts_with(&format!("docker run -d -p {port}:{port} -v Qwen/Qwen2.5-0.5B:Qwen/Qwen2.5-0.5B ")));
+        assert!(calls[2].contains(" --name vllm-test vllm/vllm-openai:latest --model Qwen/Qwen2.5-0.5B "));
+        assert!(!calls[2].contains("--runtime nvidia"));
+        assert_eq!(
+            &calls[3..],
+            [
+                "docker inspect -f {{.State.Running}} abc123",
+                "docker logs abc123 --tail 20",
+                "docker stop abc123",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_classifies_docker_failure() {
+        use crate::command::MockExecutor;
+        use crate::error::DockerFailure;
+
+        let docker = MockExecutor::new();
+        docker.push_reply(true, "Docker version 27.3.1\n", ""); // --version
+        docker.push_reply(false, "", "docker: Error response from daemon: pull access denied for vllm/nope"); // run
+        let mut container = VllmContainer::new(VllmConfig::new("model").with_image("vllm/nope"))
+            .with_executor(docker)
+            .with_gpu(false);
+
+        let err = container.start().await.unwrap_err();
+        assert!(matches!(err, OrchestratorError::DockerFailed { kind: DockerFailure::ImagePull, .. }));
+        assert!(err.to_string().contains("docker login"));
+    }
+
+    #[tokio::test]
+    async fn test_start_stops_container_when_warmup_fails() {
+        use crate::command::MockExecutor;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Healthy server that can't actually generate
+        let vllm = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&vllm)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{"id": "bad/model-path"}]
+            })))
+            .mount(&vllm)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/completions"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&vllm)
+            .await;
+
+        let docker = MockExecutor::new();
+        docker.push_reply(true, "Docker version 27.3.1\n", ""); // --version
+        docker.push_reply(true, "abc123\n", ""); // run
+        let mut config = VllmConfig::new("bad/model-path")
+            .with_port(vllm.address().port())
+            .with_warmup_prompt("Hello");
+        config.host = "127.0.0.1".to_string();
+        let mut container = VllmContainer::new(config)
+            .with_executor(docker.clone())
+            .with_gpu(false);
+
+        let err = container.start().await.unwrap_err();
+        assert!(err.to_string().contains("Warmup completion failed"));
+        assert_eq!(docker.calls().last().unwrap(), "docker stop abc123");
+    }
+
+    #[test]
+    fn test_readiness_failure_classified_from_logs() {
+        use crate::error::DockerFailure;
+
+        let logs = "INFO Loading weights\nRuntimeError: CUDA error: no kernel image is available for execution on the device\n";
+        let err = readiness_failure(logs, Some(1));
+        assert!(matches!(err, OrchestratorError::DockerFailed { kind: DockerFailure::Cuda, .. }));
+        assert!(err.to_string().contains("exited with code 1"));
+
+        let err = readiness_failure("INFO Loading weights\n", Some(137));
+        assert!(matches!(err, OrchestratorError::DockerFailed { kind: DockerFailure::OutOfMemory, .. }));
+
+        // Still running, nothing recognisable
+        let err = readiness_failure("INFO Loading weights\n", Some(0));
+        assert_eq!(err.to_string(), "Docker error: vLLM did not become ready within 10 minutes");
+    }
+
+    #[tokio::test]
+    async fn test_start_without_docker() {
+        use crate::command::MockExecutor;
+
+        let docker = MockExecutor::new();
+        docker.push_error(std::io::ErrorKind::NotFound);
+        let mut container = VllmContainer::new(VllmConfig::new("model"))
+            .with_executor(docker.clone())
+            .with_gpu(true);
+
+        let err = container.start().await.unwrap_err();
+        assert!(matches!(err, OrchestratorError::DockerNotInstalled));
+        assert!(err.to_string().contains("install Docker"));
+        // Nothing else is attempted
+        assert_eq!(docker.calls(), ["docker --version"]);
+
+        // GPU hosts also look for the nvidia runtime
+        container.check_docker_available().await.unwrap();
+        assert_eq!(
+            &docker.calls()[1..],
+            ["docker --version", "docker info --format {{json .Runtimes}}"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_unsupported_on_gpu() {
+        use crate::command::MockExecutor;
+
+        let docker = MockExecutor::new();
+        let mut container = VllmContainer::new(VllmConfig::new("model"))
+            .with_executor(docker.clone())
+            .with_gpu(true);
+        container.container_id = Some("abc123".to_string());
+
+        let err = container.checkpoint("cp1").await.unwrap_err();
+        assert!(matches!(err, OrchestratorError::Unsupported(_)));
+        assert!(docker.calls().is_empty());
+
+        // CPU-only hosts keep the Docker checkpoint
+        container.gpu = false;
+        container.checkpoint("cp1").await.unwrap();
+        assert_eq!(
+            docker.calls(),
+            ["docker checkpoint create --checkpoint-dir=/tmp/checkpoints --leave=true abc123 cp1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_models_detailed() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Response shape of vLLM's OpenAI-compatible server
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [{
+                    "id": "Qwen/Qwen2.5-7B",
+                    "object": "model",
+                    "created": 1718000000,
+                    "owned_by": "vllm",
+                    "root": "Qwen/Qwen2.5-7B",
+                    "parent": null,
+                    "max_model_len": 32768,
+                    "permission": []
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = VllmClient::new(server.uri());
+        let models = client.list_models_detailed().await.unwrap();
+        assert_eq!(
+            models,
+            [ModelInfo {
+                id: "Qwen/Qwen2.5-7B".to_string(),
+                max_model_len: Some(32768),
+                owned_by: Some("vllm".to_string()),
+                created: Some(1718000000),
+            }]
+        );
+        assert_eq!(client.list_models().await.unwrap(), ["Qwen/Qwen2.5-7B"]);
+
+        let config = VllmConfig::new("Qwen/Qwen2.5-7B").with_max_model_len(8192);
+        let warning = config.context_length_warning(&models[0]).unwrap();
+        assert!(warning.contains("max_model_len=32768"));
+        assert!(config.with_max_model_len(32768).context_length_warning(&models[0]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_completion() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let healthy = MockServer::start().await;
+        let broken = MockServer::start().await;
+        for server in [&healthy, &broken] {
+            Mock::given(method("GET"))
+                .and(path("/v1/models"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": [{"id": "Qwen/Qwen2.5-7B"}]
+                })))
+                .mount(server)
+                .await;
+        }
+        Mock::given(method("POST"))
+            .and(path("/v1/completions"))
+            .and(body_partial_json(serde_json::json!({"model": "Qwen/Qwen2.5-7B", "max_tokens": 4})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"index": 0, "text": " world"}]
+            })))
+            .mount(&healthy)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/completions"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&broken)
+            .await;
+
+        let text = VllmClient::new(healthy.uri()).completion("Hello", 4).await.unwrap();
+        assert_eq!(text, " world");
+
+        let err = VllmClient::new(broken.uri()).completion("Hello", 4).await.unwrap_err();
+        assert!(matches!(err, OrchestratorError::HealthCheck(_)));
+    }
+
+    #[tokio::test]
+    async fn test_running_requests_fallback() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Metrics endpoint up but without request gauges (--disable-log-stats)
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/metrics"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("# no vllm gauges\n"))
+            .mount(&server)
+            .await;
+
+        let client = VllmClient::new(server.uri());
+        assert_eq!(client.get_running_requests().await.unwrap(), RequestCount::Unknown);
+        assert_eq!(client.get_waiting_requests().await.unwrap(), RequestCount::Unknown);
+        assert!(!client.is_idle().await.unwrap(), "unknown must not look idle");
+
+        Mock::given(method("GET"))
+            .and(path("/load"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"server_load": 3})),
+            )
+            .mount(&server)
+            .await;
+        assert_eq!(client.get_running_requests().await.unwrap(), RequestCount::Known(3));
+
+        // /load already counts queued requests
+        assert_eq!(
+            client.get_request_counts().await.unwrap(),
+            (RequestCount::Known(3), RequestCount::Known(0))
+        );
+
+        // Unreachable server is an error, not a count
+        let client = VllmClient::new("http://127.0.0.1:1");
+        assert!(client.get_running_requests().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_missing_waiting_metric_is_not_idle() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Running gauge exported, waiting gauge missing
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/metrics"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string("vllm:num_requests_running 0\n"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = VllmClient::new(server.uri());
+        assert_eq!(
+            client.get_request_counts().await.unwrap(),
+            (RequestCount::Known(0), RequestCount::Unknown)
+        );
+        assert!(!client.is_idle().await.unwrap(), "unknown waiting must not look idle");
+    }
+
+    #[test]
+    fn test_find_metric_value() {
+        let metrics = "# HELP vllm:num_requests_running Running\n\
+                       vllm:num_requests_running{model_name=\"m\"} 4.0\n\
+                       vllm_num_requests_waiting 2\n";
+        let running = ["vllm:num_requests_running", "vllm_num_requests_running"];
+        let waiting = ["vllm:num_requests_waiting", "vllm_num_requests_waiting"];
+        assert_eq!(find_metric_value(metrics, &running), Some(4));
+        assert_eq!(find_metric_value(metrics, &waiting), Some(2));
+        assert_eq!(find_metric_value("", &running), None);
+    }
+
+    #[test]
+    fn test_sum_metric_values() {
+        let metrics = "# TYPE vllm:request_success_total counter\n\
+                       vllm:request_success_total{finished_reason=\"stop\"} 12.0\n\
+                       vllm:request_success_total{finished_reason=\"length\"} 3.0\n\
+                       vllm:request_success_total_created 1.7e9\n";
+        let success = ["vllm:request_success_total", "vllm_request_success_total"];
+        assert_eq!(sum_metric_values(metrics, &success), Some(15.0));
+        assert_eq!(sum_metric_values("vllm:num_requests_running 1\n", &success), None);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_json() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"status": "ok"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/loading"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"status": "loading"})),
+            )
+            .mount(&server)
+            .await;
+
+        let client = VllmClient::new(server.uri());
+        assert!(client.health_check_json("status", "ok").await.unwrap());
+        assert!(!client.health_check_json("state", "ok").await.unwrap());
+
+        let client = VllmClient::new(server.uri()).with_health_path("/loading");
+        assert!(!client.health_check_json("status", "ok").await.unwrap());
+
+        let client = VllmClient::new("http://127.0.0.1:1");
+        assert!(!client.health_check_json("status", "ok").await.unwrap());
     }
 }
@@ -3,10 +3,11 @@
 //! Each command talks to the fleet API over HTTP.
 //! The CLI is a thin client - all intelligence is in the fleet.
 
+pub mod status;
+
 // TODO: Implement command handlers
 // pub mod login;
 // pub mod apply;
-// pub mod status;
 // pub mod logs;
 // pub mod destroy;
 // pub mod dev;
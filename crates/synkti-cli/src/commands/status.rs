@@ -0,0 +1,66 @@
+//! `synkti status` output
+//!
+//! Human output goes through tracing like the rest of the CLI. JSON goes
+//! straight to stdout so it can be piped into other tools.
+
+use synkti_core::FleetStatus;
+use tracing::info;
+
+/// Print `status` to stdout as JSON
+pub fn print_json(status: &FleetStatus) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(status)?);
+    Ok(())
+}
+
+/// Log a human-readable summary of `status`
+pub fn log_summary(project: Option<&str>, status: &FleetStatus) {
+    info!("Status for project: {}", project.unwrap_or("(all)"));
+    info!(
+        "{} nodes, {} healthy, {} requests in flight",
+        status.nodes.len(),
+        status.healthy_count(),
+        status.running_requests()
+    );
+    for node in &status.nodes {
+        info!(
+            "  {} ({}) {:?} {:?} model: {}",
+            node.instance_id,
+            node.instance_type.as_deref().unwrap_or("unknown type"),
+            node.spot_state,
+            node.health,
+            node.model.as_deref().unwrap_or("-")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use synkti_core::{HealthStatus, NodeStatus, SpotState};
+
+    #[test]
+    fn test_json_fields() {
+        let status = FleetStatus {
+            nodes: vec![NodeStatus {
+                instance_id: "i-0abc".to_string(),
+                instance_type: Some("g5.xlarge".to_string()),
+                spot_state: SpotState::Active,
+                health: HealthStatus::Healthy,
+                model: Some("Qwen/Qwen2.5-7B".to_string()),
+                running_requests: Some(2),
+                uptime_seconds: 120,
+            }],
+        };
+
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string_pretty(&status).unwrap()).unwrap();
+        let node = &json["nodes"][0];
+        assert_eq!(node["instance_id"], "i-0abc");
+        assert_eq!(node["instance_type"], "g5.xlarge");
+        assert_eq!(node["spot_state"], "active");
+        assert_eq!(node["health"], "healthy");
+        assert_eq!(node["model"], "Qwen/Qwen2.5-7B");
+        assert_eq!(node["running_requests"], 2);
+        assert_eq!(node["uptime_seconds"], 120);
+    }
+}
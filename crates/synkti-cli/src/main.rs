@@ -6,10 +6,12 @@
 //! - View fleet status
 //! - Stream logs
 //! - Destroy infrastructure
+//! - Worker operations (model swap, rolling restart, container logs)
 //!
 //! Binary: synkti
 
 use clap::{Parser, Subcommand};
+use synkti_core::{AwsRegion, DEFAULT_REGION, FleetStatus};
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -41,12 +43,20 @@ enum Commands {
         /// Config file path
         #[arg(short, long, default_value = "synkti.yaml")]
         config: String,
+
+        /// AWS region to deploy into
+        #[arg(long, env = "AWS_REGION", default_value = DEFAULT_REGION)]
+        region: AwsRegion,
     },
 
     /// Show fleet status
     Status {
         /// Project name
         project: Option<String>,
+
+        /// Print machine-readable JSON to stdout instead of log lines
+        #[arg(long)]
+        json: bool,
     },
 
     /// Stream logs from fleet
@@ -75,6 +85,59 @@ enum Commands {
         #[arg(long)]
         model: String,
     },
+
+    /// Operate on individual workers
+    Worker {
+        #[command(subcommand)]
+        command: WorkerCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorkerCommands {
+    /// Drain a worker and restart it serving a different model
+    SwapModel {
+        /// Model to serve (HuggingFace model ID)
+        #[arg(long)]
+        model: String,
+
+        /// Worker instance ID (all workers if omitted)
+        #[arg(long)]
+        instance: Option<String>,
+    },
+
+    /// Restart every worker on a new model/image, a batch at a time
+    RollingRestart {
+        /// Model to serve (HuggingFace model ID)
+        #[arg(long)]
+        model: String,
+
+        /// vLLM Docker image (keeps the current image if omitted)
+        #[arg(long)]
+        image: Option<String>,
+
+        /// Maximum number of workers out of service at once
+        #[arg(long, default_value = "1", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        batch_size: usize,
+    },
+
+    /// Fetch vLLM container logs from a worker
+    Logs {
+        /// Worker instance ID
+        instance_id: String,
+
+        /// Number of lines from the end of the logs
+        #[arg(long, default_value = "100")]
+        tail: u32,
+
+        /// Keep polling for new lines
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Container name (defaults to the worker's vLLM container)
+        #[arg(long)]
+        container: Option<String>,
+    },
 }
 
 #[tokio::main]
@@ -95,13 +158,25 @@ async fn main() -> anyhow::Result<()> {
             info!("Login not yet implemented");
             // TODO: Implement OAuth/API key auth
         }
-        Commands::Apply { project, config } => {
-            info!("Deploying project '{}' with config '{}'", project, config);
+        Commands::Apply {
+            project,
+            config,
+            region,
+        } => {
+            info!(
+                "Deploying project '{}' with config '{}' to {}",
+                project, config, region
+            );
             // TODO: Call fleet API to deploy
         }
-        Commands::Status { project } => {
-            info!("Status for project: {:?}", project);
-            // TODO: Call fleet API to get status
+        Commands::Status { project, json } => {
+            // TODO: Fetch from the fleet API; empty until it exists
+            let status = FleetStatus::default();
+            if json {
+                commands::status::print_json(&status)?;
+            } else {
+                commands::status::log_summary(project.as_deref(), &status);
+            }
         }
         Commands::Logs { project, follow } => {
             info!("Logs for project '{}' (follow: {})", project, follow);
@@ -115,7 +190,121 @@ async fn main() -> anyhow::Result<()> {
             info!("Starting local dev mode with model '{}'", model);
             // TODO: Run single-node vLLM locally
         }
+        Commands::Worker {
+            command: WorkerCommands::SwapModel { model, instance },
+        } => {
+            info!("Swapping model to '{}' on worker: {:?}", model, instance);
+            // TODO: Call fleet API to swap the model (agent runs VllmContainer::swap_model)
+        }
+        Commands::Worker {
+            command:
+                WorkerCommands::RollingRestart {
+                    model,
+                    image,
+                    batch_size,
+                },
+        } => {
+            info!(
+                "Rolling restart to model '{}' (image: {:?}, batch size: {})",
+                model, image, batch_size
+            );
+            // TODO: Call fleet API to roll workers (control plane runs rolling::rolling_restart)
+        }
+        Commands::Worker {
+            command:
+                WorkerCommands::Logs {
+                    instance_id,
+                    tail,
+                    follow,
+                    container,
+                },
+        } => {
+            info!(
+                "Logs for worker '{}' (tail: {}, follow: {}, container: {:?})",
+                instance_id, tail, follow, container
+            );
+            // TODO: Fetch container logs through the fleet API
+        }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_logs_args() {
+        let cli = Cli::try_parse_from(["synkti", "worker", "logs", "i-0abc", "--tail", "50", "-f"])
+            .unwrap();
+        match cli.command {
+            Commands::Worker {
+                command:
+                    WorkerCommands::Logs {
+                        instance_id,
+                        tail,
+                        follow,
+                        container,
+                    },
+            } => {
+                assert_eq!(instance_id, "i-0abc");
+                assert_eq!(tail, 50);
+                assert!(follow);
+                assert_eq!(container, None);
+            }
+            _ => panic!("expected worker logs"),
+        }
+
+        let cli = Cli::try_parse_from(["synkti", "worker", "logs", "i-0abc", "--container", "vllm"])
+            .unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Worker {
+                command: WorkerCommands::Logs { tail: 100, follow: false, container: Some(_), .. }
+            }
+        ));
+    }
+
+    #[test]
+    fn test_worker_rolling_restart_args() {
+        let cli = Cli::try_parse_from([
+            "synkti", "worker", "rolling-restart", "--model", "Qwen/Qwen2.5-7B", "--batch-size", "2",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Worker {
+                command: WorkerCommands::RollingRestart { image: None, batch_size: 2, .. }
+            }
+        ));
+
+        let result = Cli::try_parse_from([
+            "synkti", "worker", "rolling-restart", "--model", "Qwen/Qwen2.5-7B", "--batch-size", "0",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_status_json_flag() {
+        let cli = Cli::try_parse_from(["synkti", "status", "demo", "--json"]).unwrap();
+        assert!(matches!(cli.command, Commands::Status { project: Some(_), json: true }));
+
+        let cli = Cli::try_parse_from(["synkti", "status"]).unwrap();
+        assert!(matches!(cli.command, Commands::Status { project: None, json: false }));
+    }
+
+    #[test]
+    fn test_apply_region_validation() {
+        let cli = Cli::try_parse_from(["synkti", "apply", "demo", "--region", "eu-west-1"]).unwrap();
+        match cli.command {
+            Commands::Apply { region, .. } => assert_eq!(region.as_str(), "eu-west-1"),
+            _ => panic!("expected apply"),
+        }
+
+        let err = Cli::try_parse_from(["synkti", "apply", "demo", "--region", "us-east1"])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Unknown AWS region 'us-east1'"));
+    }
+}
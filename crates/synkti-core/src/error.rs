@@ -23,6 +23,12 @@ pub enum SynktiError {
     #[error("Authentication error: {0}")]
     Auth(String),
 
+    #[error(
+        "User-data is {size} bytes, over the EC2 limit of {}; fetch large scripts from S3 in a small bootstrap instead",
+        crate::user_data::MAX_USER_DATA_BYTES
+    )]
+    UserDataTooLarge { size: usize },
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
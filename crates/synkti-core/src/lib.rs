@@ -8,12 +8,24 @@
 //! Key types:
 //! - SpotProvider trait (interface for cloud providers)
 //! - Instance types and health status
+//! - Node and fleet status reports
+//! - Validated AWS regions
+//! - User-data template rendering
+//! - Model weight sources (S3, EBS snapshot, local path)
 //! - Error types
 
 pub mod types;
 pub mod traits;
 pub mod error;
+pub mod region;
+pub mod status;
+pub mod user_data;
+pub mod model_source;
 
 pub use types::*;
 pub use traits::*;
 pub use error::*;
+pub use region::*;
+pub use status::*;
+pub use user_data::*;
+pub use model_source::*;
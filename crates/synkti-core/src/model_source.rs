@@ -0,0 +1,212 @@
+//! Where workers get model weights
+//!
+//! Cold starts are dominated by pulling weights. Syncing from S3 is the
+//! simplest option. Restoring a pre-baked EBS snapshot skips the download:
+//! the volume is attached at launch and blocks are fetched lazily as vLLM
+//! reads them. A local path is for weights already on the image.
+
+use serde::{Deserialize, Serialize};
+
+/// Device name the model snapshot volume is attached as
+///
+/// On Nitro instances the volume shows up as an NVMe device; Amazon Linux
+/// keeps `/dev/sdf` as a symlink to it.
+pub const MODEL_VOLUME_DEVICE: &str = "/dev/sdf";
+
+/// Source of a worker's model weights
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ModelSource {
+    /// `aws s3 sync` the weights from an S3 prefix
+    S3Sync { uri: String },
+    /// Attach a volume restored from an EBS snapshot holding the weights
+    EbsSnapshot { snapshot_id: String },
+    /// Weights already present on the instance
+    LocalPath { path: String },
+}
+
+/// AWS CLI transfer settings for syncing large weights from S3
+///
+/// The CLI defaults (10 concurrent requests, 8MB parts) use a fraction of a
+/// GPU instance's network bandwidth. Raising them typically cuts a multi-GB
+/// sync several times over on 25Gbps+ instances; the exact gain depends on
+/// file sizes and the instance's network limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct S3TransferConfig {
+    pub max_concurrent_requests: u32,
+    /// Files larger than this are downloaded in parts (MB)
+    pub multipart_threshold_mb: u32,
+    /// Size of each part (MB)
+    pub multipart_chunksize_mb: u32,
+}
+
+impl S3TransferConfig {
+    /// `aws configure` commands applying these settings
+    pub fn configure_commands(&self) -> Vec<String> {
+        vec![
+            format!(
+                "aws configure set default.s3.max_concurrent_requests {}",
+                self.max_concurrent_requests
+            ),
+            format!(
+                "aws configure set default.s3.multipart_threshold {}MB",
+                self.multipart_threshold_mb
+            ),
+            format!(
+                "aws configure set default.s3.multipart_chunksize {}MB",
+                self.multipart_chunksize_mb
+            ),
+        ]
+    }
+}
+
+impl Default for S3TransferConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: 64,
+            multipart_threshold_mb: 64,
+            multipart_chunksize_mb: 64,
+        }
+    }
+}
+
+/// Extra EBS volume to attach at launch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockDeviceMapping {
+    pub device_name: String,
+    pub snapshot_id: String,
+    /// EBS volume type (e.g. `gp3`)
+    pub volume_type: String,
+    pub delete_on_termination: bool,
+}
+
+impl ModelSource {
+    /// Volume to attach at launch, for snapshot sources
+    pub fn block_device(&self) -> Option<BlockDeviceMapping> {
+        match self {
+            Self::EbsSnapshot { snapshot_id } => Some(BlockDeviceMapping {
+                device_name: MODEL_VOLUME_DEVICE.to_string(),
+                snapshot_id: snapshot_id.clone(),
+                volume_type: "gp3".to_string(),
+                delete_on_termination: true,
+            }),
+            Self::S3Sync { .. } | Self::LocalPath { .. } => None,
+        }
+    }
+
+    /// Bootstrap commands that put the weights at `model_dir`
+    ///
+    /// Run before the container starts. A local path already at `model_dir`
+    /// needs no commands; one elsewhere is bind-mounted there.
+    pub fn bootstrap_commands(&self, model_dir: &str) -> Vec<String> {
+        self.bootstrap_commands_with(model_dir, None)
+    }
+
+    /// `bootstrap_commands`, tuning the AWS CLI first when syncing from S3
+    pub fn bootstrap_commands_with(
+        &self,
+        model_dir: &str,
+        transfer: Option<&S3TransferConfig>,
+    ) -> Vec<String> {
+        let dir = quote(model_dir);
+        match self {
+            Self::S3Sync { uri } => {
+                let mut commands = transfer
+                    .map(S3TransferConfig::configure_commands)
+                    .unwrap_or_default();
+                commands.push(format!("mkdir -p {}", dir));
+                commands.push(format!("aws s3 sync {} {} --only-show-errors", quote(uri), dir));
+                commands
+            }
+            Self::EbsSnapshot { .. } => vec![
+                format!("mkdir -p {}", dir),
+                format!("mount -o ro {} {}", MODEL_VOLUME_DEVICE, dir),
+            ],
+            Self::LocalPath { path } if path.trim_end_matches('/') == model_dir.trim_end_matches('/') => {
+                Vec::new()
+            }
+            Self::LocalPath { path } => vec![
+                format!("mkdir -p {}", dir),
+                format!("mount --bind {} {}", quote(path), dir),
+            ],
+        }
+    }
+}
+
+/// Single-quote a value for a POSIX shell
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_device_per_source() {
+        let snapshot = ModelSource::EbsSnapshot {
+            snapshot_id: "snap-0abc".to_string(),
+        };
+        assert_eq!(
+            snapshot.block_device(),
+            Some(BlockDeviceMapping {
+                device_name: "/dev/sdf".to_string(),
+                snapshot_id: "snap-0abc".to_string(),
+                volume_type: "gp3".to_string(),
+                delete_on_termination: true,
+            })
+        );
+
+        let s3 = ModelSource::S3Sync {
+            uri: "s3://models/qwen".to_string(),
+        };
+        let local = ModelSource::LocalPath {
+            path: "/opt/models/qwen".to_string(),
+        };
+        assert_eq!(s3.block_device(), None);
+        assert_eq!(local.block_device(), None);
+    }
+
+    #[test]
+    fn test_bootstrap_commands() {
+        let s3 = ModelSource::S3Sync {
+            uri: "s3://models/qwen".to_string(),
+        };
+        assert_eq!(
+            s3.bootstrap_commands("/models/qwen"),
+            [
+                "mkdir -p '/models/qwen'",
+                "aws s3 sync 's3://models/qwen' '/models/qwen' --only-show-errors",
+            ]
+        );
+
+        let snapshot = ModelSource::EbsSnapshot {
+            snapshot_id: "snap-0abc".to_string(),
+        };
+        assert_eq!(snapshot.bootstrap_commands("/models")[1], "mount -o ro /dev/sdf '/models'");
+
+        // Weights baked in elsewhere on the image are mounted where vLLM looks
+        let local = ModelSource::LocalPath {
+            path: "/opt/models/qwen".to_string(),
+        };
+        assert!(local.bootstrap_commands("/opt/models/qwen/").is_empty());
+        assert_eq!(
+            local.bootstrap_commands("/models/qwen"),
+            ["mkdir -p '/models/qwen'", "mount --bind '/opt/models/qwen' '/models/qwen'"]
+        );
+
+        // Transfer tuning only applies to S3 syncs
+        let tuning = S3TransferConfig::default();
+        let script = s3.bootstrap_commands_with("/models/qwen", Some(&tuning)).join("\n");
+        assert!(script.starts_with("aws configure set default.s3.max_concurrent_requests 64\n"));
+        assert!(script.contains("aws configure set default.s3.multipart_threshold 64MB\n"));
+        assert!(script.contains("aws configure set default.s3.multipart_chunksize 64MB\n"));
+        assert!(!snapshot
+            .bootstrap_commands_with("/models", Some(&tuning))
+            .iter()
+            .any(|c| c.starts_with("aws configure")));
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(json, r#"{"type":"ebs_snapshot","snapshot_id":"snap-0abc"}"#);
+    }
+}
@@ -0,0 +1,107 @@
+//! AWS region names
+//!
+//! Regions are validated up front so a typo (`us-east1`) fails at argument
+//! parsing rather than as an opaque SDK error on the first API call.
+
+use crate::error::SynktiError;
+use serde::{Deserialize, Serialize};
+
+/// Region used when none is configured
+pub const DEFAULT_REGION: &str = "us-east-1";
+
+/// Known AWS region codes (commercial, GovCloud and China partitions)
+pub const KNOWN_REGIONS: &[&str] = &[
+    "us-east-1", "us-east-2", "us-west-1", "us-west-2",
+    "af-south-1",
+    "ap-east-1", "ap-east-2", "ap-south-1", "ap-south-2",
+    "ap-southeast-1", "ap-southeast-2", "ap-southeast-3", "ap-southeast-4",
+    "ap-southeast-5", "ap-southeast-6", "ap-southeast-7",
+    "ap-northeast-1", "ap-northeast-2", "ap-northeast-3",
+    "ca-central-1", "ca-west-1",
+    "eu-central-1", "eu-central-2", "eu-west-1", "eu-west-2", "eu-west-3",
+    "eu-south-1", "eu-south-2", "eu-north-1",
+    "il-central-1", "me-south-1", "me-central-1", "mx-central-1", "sa-east-1",
+    "us-gov-east-1", "us-gov-west-1",
+    "cn-north-1", "cn-northwest-1",
+];
+
+/// A validated AWS region code
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct AwsRegion(String);
+
+impl AwsRegion {
+    /// Region code as passed to the AWS SDK
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for AwsRegion {
+    fn default() -> Self {
+        Self(DEFAULT_REGION.to_string())
+    }
+}
+
+impl std::fmt::Display for AwsRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for AwsRegion {
+    type Err = SynktiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let region = s.trim().to_ascii_lowercase();
+        if KNOWN_REGIONS.contains(&region.as_str()) {
+            Ok(Self(region))
+        } else {
+            Err(SynktiError::Config(format!(
+                "Unknown AWS region '{}'. Valid regions: {}",
+                s,
+                KNOWN_REGIONS.join(", ")
+            )))
+        }
+    }
+}
+
+impl TryFrom<String> for AwsRegion {
+    type Error = SynktiError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<AwsRegion> for String {
+    fn from(region: AwsRegion) -> Self {
+        region.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_parsing() {
+        let region: AwsRegion = "us-west-2".parse().unwrap();
+        assert_eq!(region.as_str(), "us-west-2");
+        assert_eq!(" EU-Central-1 ".parse::<AwsRegion>().unwrap().as_str(), "eu-central-1");
+        assert_eq!(AwsRegion::default().as_str(), DEFAULT_REGION);
+
+        let err = "us-east1".parse::<AwsRegion>().unwrap_err().to_string();
+        assert!(err.contains("Unknown AWS region 'us-east1'"));
+        assert!(err.contains("us-east-1, us-east-2"));
+        assert!("".parse::<AwsRegion>().is_err());
+    }
+
+    #[test]
+    fn test_region_serde() {
+        let region: AwsRegion = "ap-south-1".parse().unwrap();
+        assert_eq!(serde_json::to_string(&region).unwrap(), "\"ap-south-1\"");
+        assert_eq!(serde_json::from_str::<AwsRegion>("\"ap-south-1\"").unwrap(), region);
+        assert!(serde_json::from_str::<AwsRegion>("\"mars-north-1\"").is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Node and fleet status
+//!
+//! One wire format for status across the agent's `/status` endpoint, the
+//! CLI's `status` command and the fleet dashboard.
+
+use crate::types::HealthStatus;
+use serde::{Deserialize, Serialize};
+
+/// Where a node is in the spot lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpotState {
+    /// Spot capacity with no pending interruption
+    Active,
+    /// Interruption notice received; draining before the instance goes away
+    Draining,
+    /// Not a spot instance
+    OnDemand,
+}
+
+/// Status of a single node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeStatus {
+    pub instance_id: String,
+    /// e.g. `g5.xlarge`, when known
+    pub instance_type: Option<String>,
+    pub spot_state: SpotState,
+    pub health: HealthStatus,
+    /// Model being served, once loaded
+    pub model: Option<String>,
+    /// Requests in flight, when the server reports them
+    pub running_requests: Option<u32>,
+    pub uptime_seconds: u64,
+}
+
+/// Status of every node in a fleet
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FleetStatus {
+    pub nodes: Vec<NodeStatus>,
+}
+
+impl FleetStatus {
+    /// Nodes reporting healthy
+    pub fn healthy_count(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| node.health == HealthStatus::Healthy)
+            .count()
+    }
+
+    /// Requests in flight across nodes that report them
+    pub fn running_requests(&self) -> u32 {
+        self.nodes.iter().filter_map(|node| node.running_requests).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_round_trip() {
+        let fleet = FleetStatus {
+            nodes: vec![
+                NodeStatus {
+                    instance_id: "i-0abc".to_string(),
+                    instance_type: Some("g5.xlarge".to_string()),
+                    spot_state: SpotState::Active,
+                    health: HealthStatus::Healthy,
+                    model: Some("Qwen/Qwen2.5-7B".to_string()),
+                    running_requests: Some(3),
+                    uptime_seconds: 3600,
+                },
+                NodeStatus {
+                    instance_id: "i-0def".to_string(),
+                    instance_type: None,
+                    spot_state: SpotState::Draining,
+                    health: HealthStatus::Draining,
+                    model: None,
+                    running_requests: None,
+                    uptime_seconds: 60,
+                },
+            ],
+        };
+
+        let json = serde_json::to_value(&fleet).unwrap();
+        assert_eq!(json["nodes"][1]["spot_state"], "draining");
+        assert_eq!(json["nodes"][0]["health"], "healthy");
+        assert_eq!(serde_json::from_value::<FleetStatus>(json).unwrap(), fleet);
+        assert_eq!(fleet.healthy_count(), 1);
+        assert_eq!(fleet.running_requests(), 3);
+    }
+}
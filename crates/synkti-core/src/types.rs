@@ -20,6 +20,7 @@ impl std::fmt::Display for InstanceId {
 
 /// Instance health status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum HealthStatus {
     Healthy,
     Unhealthy,
@@ -72,4 +73,32 @@ pub struct LaunchConfig {
     pub region: String,
     pub tags: Vec<(String, String)>,
     pub iam_profile: Option<String>,
+    /// Base64-encoded user-data script
+    #[serde(default)]
+    pub user_data: Option<String>,
+    /// Extra volume to attach, e.g. a model weights snapshot
+    #[serde(default)]
+    pub additional_block_device: Option<crate::model_source::BlockDeviceMapping>,
+}
+
+impl LaunchConfig {
+    /// Attach base64-encoded user-data
+    ///
+    /// Checked here rather than at the launch call, so an oversized or
+    /// malformed script fails before anything is requested from the provider.
+    pub fn with_user_data(
+        mut self,
+        encoded: impl Into<String>,
+    ) -> Result<Self, crate::error::SynktiError> {
+        let encoded = encoded.into();
+        crate::user_data::validate_user_data(&encoded)?;
+        self.user_data = Some(encoded);
+        Ok(self)
+    }
+
+    /// Attach whatever volume `source` needs to serve its weights
+    pub fn with_model_source(mut self, source: &crate::model_source::ModelSource) -> Self {
+        self.additional_block_device = source.block_device();
+        self
+    }
 }
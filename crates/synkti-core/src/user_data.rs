@@ -0,0 +1,160 @@
+//! User-data templates
+//!
+//! Instance bootstrap scripts are stored as templates with `${name}`
+//! placeholders that are filled in at launch. Rendering is strict: every
+//! placeholder must have a value, so an instance never boots with a literal
+//! `${foo}` in its script. Shell expansions that should survive rendering are
+//! written with a doubled dollar (`$${HOME}` renders as `${HOME}`).
+//!
+//! Rendered scripts are sent base64-encoded, and EC2 rejects anything over
+//! 16KB once decoded; `validate_user_data` catches that before launch.
+
+use crate::error::SynktiError;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::collections::HashMap;
+
+/// Largest user-data EC2 accepts, in bytes before base64 encoding
+pub const MAX_USER_DATA_BYTES: usize = 16 * 1024;
+
+/// Expand `${name}` placeholders in `template` from `vars`
+///
+/// `$$` renders as a single `$`; any other `$` is copied through unchanged.
+/// Fails if a placeholder has no value in `vars`, is empty, or is never
+/// closed. All missing names are reported together.
+pub fn render_user_data(
+    template: &str,
+    vars: &HashMap<String, String>,
+) -> Result<String, SynktiError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut missing: Vec<&str> = Vec::new();
+    let mut rest = template;
+
+    while let Some(pos) = rest.find('$') {
+        rendered.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(tail) = after.strip_prefix('$') {
+            rendered.push('$');
+            rest = tail;
+        } else if let Some(body) = after.strip_prefix('{') {
+            let end = body.find('}').ok_or_else(|| {
+                SynktiError::Config(format!(
+                    "Unterminated placeholder in user-data: ${{{}",
+                    body.lines().next().unwrap_or_default()
+                ))
+            })?;
+            let name = &body[..end];
+            if name.is_empty() {
+                return Err(SynktiError::Config(
+                    "Empty placeholder ${} in user-data".to_string(),
+                ));
+            }
+            match vars.get(name) {
+                Some(value) => rendered.push_str(value),
+                None if !missing.contains(&name) => missing.push(name),
+                None => {}
+            }
+            rest = &body[end + 1..];
+        } else {
+            rendered.push('$');
+            rest = after;
+        }
+    }
+    rendered.push_str(rest);
+
+    if !missing.is_empty() {
+        return Err(SynktiError::Config(format!(
+            "User-data variables not supplied: {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(rendered)
+}
+
+/// Check that `encoded` is valid base64 within the EC2 user-data limit
+///
+/// Returns the decoded size in bytes.
+pub fn validate_user_data(encoded: &str) -> Result<usize, SynktiError> {
+    let decoded = STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| SynktiError::Config(format!("User-data is not valid base64: {}", e)))?;
+    if decoded.len() > MAX_USER_DATA_BYTES {
+        return Err(SynktiError::UserDataTooLarge {
+            size: decoded.len(),
+        });
+    }
+    Ok(decoded.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LaunchConfig;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_render_user_data() {
+        let template = "aws s3 cp s3://${bucket}/bin/synkti /usr/local/bin/synkti\nsynkti-agent --model ${model} --cost $5";
+        let rendered =
+            render_user_data(template, &vars(&[("bucket", "synkti-models"), ("model", "llama")]))
+                .unwrap();
+        assert_eq!(
+            rendered,
+            "aws s3 cp s3://synkti-models/bin/synkti /usr/local/bin/synkti\nsynkti-agent --model llama --cost $5"
+        );
+    }
+
+    #[test]
+    fn test_missing_variables_reported() {
+        let err = render_user_data("${region} ${bucket} ${region}", &vars(&[]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("region, bucket"), "{}", err);
+
+        assert!(render_user_data("echo ${bucket", &vars(&[("bucket", "b")])).is_err());
+        assert!(render_user_data("echo ${}", &vars(&[])).is_err());
+    }
+
+    #[test]
+    fn test_dollar_escaping() {
+        let rendered =
+            render_user_data("echo $${HOME} $$$${x} ${x}$$", &vars(&[("x", "1")])).unwrap();
+        assert_eq!(rendered, "echo ${HOME} $${x} 1$");
+    }
+
+    #[test]
+    fn test_validate_user_data() {
+        let script = "#!/bin/bash\necho ok\n";
+        assert_eq!(validate_user_data(&STANDARD.encode(script)).unwrap(), script.len());
+
+        let oversized = STANDARD.encode(vec![b'#'; MAX_USER_DATA_BYTES + 1]);
+        match validate_user_data(&oversized) {
+            Err(SynktiError::UserDataTooLarge { size }) => assert_eq!(size, MAX_USER_DATA_BYTES + 1),
+            other => panic!("expected UserDataTooLarge, got {:?}", other),
+        }
+
+        assert!(matches!(validate_user_data("not base64!"), Err(SynktiError::Config(_))));
+    }
+
+    #[test]
+    fn test_launch_config_rejects_bad_user_data() {
+        let config = LaunchConfig {
+            instance_type: "g5.xlarge".to_string(),
+            region: "us-east-1".to_string(),
+            tags: vec![],
+            iam_profile: None,
+            user_data: None,
+            additional_block_device: None,
+        };
+        assert!(config.clone().with_user_data("%%%").is_err());
+        let config = config.with_user_data(STANDARD.encode("echo ok")).unwrap();
+        assert_eq!(config.user_data.as_deref(), Some("ZWNobyBvaw=="));
+    }
+}
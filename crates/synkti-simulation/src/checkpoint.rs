@@ -54,9 +54,7 @@ impl CheckpointPlanner {
         let bandwidth_mb_per_sec = instance.network_bandwidth_gbps * 125.0;
 
         // How much can we transfer in 120 seconds?
-        let transferable_mb = bandwidth_mb_per_sec * GRACE_PERIOD_SECONDS;
-
-        transferable_mb
+        bandwidth_mb_per_sec * GRACE_PERIOD_SECONDS
     }
 
     /// Estimate transfer time for a given amount of data
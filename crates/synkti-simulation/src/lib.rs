@@ -8,6 +8,5 @@ pub mod policies;
 pub mod simulator;
 pub mod migration;
 pub mod checkpoint;
-
-// Future modules (not yet implemented)
-// pub mod metrics;
+pub mod metrics;
+pub mod stats;
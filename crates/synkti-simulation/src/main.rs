@@ -3,14 +3,20 @@
 //! Command-line interface for running spot instance orchestration simulations
 
 use clap::Parser;
-use serde_json;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::fs;
 
 use synkti_simulation::{
-    policies::{GreedyPolicy, OnDemandFallbackPolicy, OnDemandOnlyPolicy},
-    simulator::Simulator,
+    metrics,
+    policies::{
+        DeadlineAwarePolicy, GreedyPolicy, RateAwarePolicy, OnDemandFallbackPolicy,
+        OnDemandOnlyPolicy, WarmPoolPolicy,
+    },
+    simulator::{SimulationResult, Simulator},
     spot_data::SpotPriceGenerator,
-    types::Task,
+    stats::PolicySummary,
+    types::{SpotPrice, Task},
 };
 
 #[derive(Parser, Debug)]
@@ -25,7 +31,7 @@ struct Args {
     #[arg(short, long, default_value_t = 100)]
     tasks: usize,
 
-    /// Policies to compare (comma-separated: greedy,fallback,ondemand)
+    /// Policies to compare (comma-separated: greedy,fallback,ondemand,deadline,warmpool,rateaware)
     #[arg(short, long, default_value = "greedy,fallback,ondemand")]
     policies: String,
 
@@ -41,9 +47,41 @@ struct Args {
     #[arg(long, default_value_t = 0.05)]
     preemption_rate: f64,
 
+    /// Give each task a deadline of arrival + duration * FACTOR (e.g. 1.5)
+    #[arg(long)]
+    deadline_slack: Option<f64>,
+
+    /// Replay AWS spot price history (CSV or JSON export) instead of synthetic prices
+    #[arg(long)]
+    price_history: Option<String>,
+
+    /// Instance boot latency in hours (e.g. 0.05 = 3 minutes)
+    #[arg(long, default_value_t = 0.0)]
+    boot_latency: f64,
+
+    /// Model load latency in hours, after the instance has booted
+    #[arg(long, default_value_t = 0.0)]
+    model_load_latency: f64,
+
+    /// Idle spot instances the warmpool policy keeps booted for failover
+    #[arg(long, default_value_t = 2)]
+    warm_pool_size: usize,
+
+    /// Number of independent runs; with more than one, report mean ± 95% CI
+    #[arg(long, default_value_t = 1, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    runs: usize,
+
+    /// Base RNG seed (run i uses seed + i); random if omitted
+    #[arg(long)]
+    seed: Option<u64>,
+
     /// Output JSON file path (optional)
     #[arg(short, long)]
     output: Option<String>,
+
+    /// Write per-timestep metrics (instances, cost, queue depth) as CSV (optional)
+    #[arg(long)]
+    metrics_output: Option<String>,
 }
 
 fn main() {
@@ -60,79 +98,66 @@ fn main() {
     println!("  Spot price: ${:.2}/hr", args.spot_price);
     println!("  Preemption rate: {:.1}%/hr\n", args.preemption_rate * 100.0);
 
-    // Generate spot price data
-    println!("Generating spot price data...");
-    let mut price_generator = SpotPriceGenerator::new(
-        args.spot_price,
-        args.on_demand_price,
-        args.preemption_rate,
-    );
-    let spot_prices = price_generator.generate(args.duration, 0.1); // 6-minute intervals
-    println!("  Generated {} price data points\n", spot_prices.len());
-
-    // Generate tasks
-    println!("Generating {} tasks...", args.tasks);
-    let tasks: Vec<Task> = (0..args.tasks)
-        .map(|i| {
-            // Random arrival time (uniform distribution over simulation duration)
-            let arrival_time = rand::random::<f64>() * args.duration * 0.8; // Arrive in first 80%
-
-            // Random duration (1-20 hours)
-            let duration = 1.0 + rand::random::<f64>() * 19.0;
+    let base_seed = args.seed.unwrap_or_else(rand::random);
+    println!("  Runs: {} (seed {})\n", args.runs, base_seed);
 
-            Task::new(i as u64, arrival_time, duration)
-        })
-        .collect();
-    println!("  Tasks created\n");
+    // Replayed history is the same for every run
+    let price_history = args.price_history.as_ref().map(|history_path| {
+        println!("Loading spot price history from {}...", history_path);
+        let loaded = if history_path.ends_with(".json") {
+            SpotPriceGenerator::from_json(history_path, 0.1, args.on_demand_price, args.preemption_rate)
+        } else {
+            SpotPriceGenerator::from_csv(history_path, 0.1, args.on_demand_price, args.preemption_rate)
+        };
+        let prices = loaded.expect("Failed to load spot price history");
+        println!("  Loaded {} price data points\n", prices.len());
+        prices
+    });
 
     // Parse policy list
     let policy_names: Vec<&str> = args.policies.split(',').map(|s| s.trim()).collect();
 
-    let mut results = Vec::new();
-
-    // Run simulation for each policy
-    for policy_name in &policy_names {
-        print!("Running simulation with {} policy... ", policy_name);
-
-        // Parse policy name and migration strategy
-        // Supports: "greedy", "greedy-naive", "greedy-optimal", etc.
-        let (base_policy, use_optimal) = if policy_name.ends_with("-naive") {
-            (policy_name.trim_end_matches("-naive"), false)
-        } else if policy_name.ends_with("-optimal") {
-            (policy_name.trim_end_matches("-optimal"), true)
-        } else {
-            // Default: use optimal for backwards compatibility
-            (*policy_name, true)
-        };
-
-        let policy_box: Box<dyn synkti_simulation::policies::SchedulingPolicy> = match base_policy {
-            "greedy" => Box::new(GreedyPolicy::new()),
-            "fallback" => Box::new(OnDemandFallbackPolicy::new(2)), // Fallback after 2 preemptions
-            "ondemand" => Box::new(OnDemandOnlyPolicy::new()),
-            _ => {
-                eprintln!("Unknown policy: {}", policy_name);
-                continue;
+    let mut all_runs: Vec<Vec<SimulationResult>> = Vec::with_capacity(args.runs);
+    for run in 0..args.runs {
+        let verbose = args.runs == 1;
+        if !verbose {
+            println!("Run {}/{}...", run + 1, args.runs);
+        }
+        let mut rng = StdRng::seed_from_u64(base_seed.wrapping_add(run as u64));
+
+        // Generate spot price data (6-minute intervals)
+        let spot_prices = match price_history {
+            Some(ref prices) => prices.clone(),
+            None => {
+                let mut price_generator = SpotPriceGenerator::new(
+                    args.spot_price,
+                    args.on_demand_price,
+                    args.preemption_rate,
+                );
+                let prices = price_generator.generate_with_rng(args.duration, 0.1, &mut rng);
+                if verbose {
+                    println!("Generated {} spot price data points", prices.len());
+                }
+                prices
             }
         };
 
-        let mut simulator = Simulator::new(
-            policy_box,
-            spot_prices.clone(),
-            args.on_demand_price,
-            use_optimal,
-        );
-
-        // Add all tasks
-        for task in tasks.clone() {
-            simulator.add_task(task);
+        let tasks = generate_tasks(&args, &mut rng);
+        if verbose {
+            println!("Generated {} tasks\n", tasks.len());
         }
 
-        // Run simulation
-        let result = simulator.run(args.duration);
-        println!("Done");
+        // Every policy sees the same preemption draws within a run
+        let sim_seed: u64 = rng.r#gen();
+        all_runs.push(run_policies(&args, &policy_names, &spot_prices, &tasks, sim_seed, verbose));
+    }
 
-        results.push(result);
+    if args.runs > 1 {
+        print_summaries(&args, &policy_names, &all_runs);
+        println!("\n✅ Simulation complete!\n");
+        return;
     }
+    let results = all_runs.pop().unwrap_or_default();
 
     // Display results
     println!("\n╔══════════════════════════════════════════════════════════╗");
@@ -165,6 +190,32 @@ fn main() {
         );
     }
 
+    // Deadline misses (only meaningful when tasks carry deadlines)
+    if args.deadline_slack.is_some() {
+        println!("\nDeadline misses:");
+        for result in &results {
+            println!("  {:<18} {:>5}/{}",
+                result.policy_name,
+                result.deadline_misses,
+                result.total_tasks,
+            );
+        }
+    }
+
+    // Warm-pool trade-off (only for runs that kept a warm pool)
+    if results.iter().any(|r| r.warm_failovers > 0 || r.warm_pool_idle_cost > 0.0) {
+        println!("\nWarm pool:");
+        for result in &results {
+            println!("  {:<18} idle ${:>8.2}   {:>4} warm / {:>4} cold failovers   {:>6.2}h saved",
+                result.policy_name,
+                result.warm_pool_idle_cost,
+                result.warm_failovers,
+                result.cold_failover_launches,
+                result.failover_latency_saved_hours,
+            );
+        }
+    }
+
     // Calculate savings (use OnDemand-only as baseline, or most expensive)
     if results.len() > 1 {
         let baseline = results.iter()
@@ -196,5 +247,149 @@ fn main() {
         println!("  Results saved");
     }
 
+    // Output time-series metrics if requested
+    if let Some(metrics_path) = args.metrics_output {
+        println!("\nWriting metrics to {}...", metrics_path);
+        let csv = metrics::series_to_csv(
+            results
+                .iter()
+                .filter_map(|r| r.metrics.as_ref().map(|m| (r.policy_name.as_str(), m))),
+        );
+        fs::write(&metrics_path, csv).expect("Failed to write metrics CSV");
+        println!("  Metrics saved");
+    }
+
     println!("\n✅ Simulation complete!\n");
 }
+
+/// Generate random tasks arriving over the first 80% of the simulation
+fn generate_tasks(args: &Args, rng: &mut StdRng) -> Vec<Task> {
+    (0..args.tasks)
+        .map(|i| {
+            // Random arrival time (uniform distribution over simulation duration)
+            let arrival_time = rng.r#gen::<f64>() * args.duration * 0.8; // Arrive in first 80%
+
+            // Random duration (1-20 hours)
+            let duration = 1.0 + rng.r#gen::<f64>() * 19.0;
+
+            let task = Task::new(i as u64, arrival_time, duration);
+            match args.deadline_slack {
+                Some(factor) => task.with_deadline(arrival_time + duration * factor),
+                None => task,
+            }
+        })
+        .collect()
+}
+
+/// Run every policy once against the same prices and tasks
+fn run_policies(
+    args: &Args,
+    policy_names: &[&str],
+    spot_prices: &[SpotPrice],
+    tasks: &[Task],
+    sim_seed: u64,
+    verbose: bool,
+) -> Vec<SimulationResult> {
+    let mut results = Vec::new();
+
+    for policy_name in policy_names {
+        if verbose {
+            print!("Running simulation with {} policy... ", policy_name);
+        }
+
+        // Parse policy name and migration strategy
+        // Supports: "greedy", "greedy-naive", "greedy-optimal", etc.
+        let (base_policy, use_optimal) = if policy_name.ends_with("-naive") {
+            (policy_name.trim_end_matches("-naive"), false)
+        } else if policy_name.ends_with("-optimal") {
+            (policy_name.trim_end_matches("-optimal"), true)
+        } else {
+            // Default: use optimal for backwards compatibility
+            (*policy_name, true)
+        };
+
+        let policy_box: Box<dyn synkti_simulation::policies::SchedulingPolicy> = match base_policy {
+            "greedy" => Box::new(GreedyPolicy::new()),
+            "fallback" => Box::new(OnDemandFallbackPolicy::new(2)), // Fallback after 2 preemptions
+            "ondemand" => Box::new(OnDemandOnlyPolicy::new()),
+            "deadline" => Box::new(DeadlineAwarePolicy::new(2.0)), // On-demand below 2h slack
+            "warmpool" => Box::new(WarmPoolPolicy::new(args.warm_pool_size)),
+            // On-demand above 0.5 preemptions/hour over the last 4 hours
+            "rateaware" => Box::new(RateAwarePolicy::new(0.5, 4.0)),
+            _ => {
+                eprintln!("Unknown policy: {}", policy_name);
+                continue;
+            }
+        };
+
+        let mut simulator = Simulator::new(
+            policy_box,
+            spot_prices.to_vec(),
+            args.on_demand_price,
+            use_optimal,
+        )
+        .with_launch_latency(args.boot_latency, args.model_load_latency)
+        .with_rng(StdRng::seed_from_u64(sim_seed));
+        if args.metrics_output.is_some() && verbose {
+            simulator = simulator.with_metrics(0.1);
+        }
+
+        // Add all tasks
+        for task in tasks.iter().cloned() {
+            simulator.add_task(task);
+        }
+
+        // Run simulation
+        let result = simulator.run(args.duration);
+        if verbose {
+            println!("Done");
+        }
+
+        results.push(result);
+    }
+
+    results
+}
+
+/// Print mean ± 95% CI per policy across runs (and write them as JSON if requested)
+fn print_summaries(args: &Args, policy_names: &[&str], all_runs: &[Vec<SimulationResult>]) {
+    let summaries: Vec<PolicySummary> = (0..policy_names.len())
+        .filter_map(|i| {
+            let per_policy: Vec<SimulationResult> =
+                all_runs.iter().filter_map(|run| run.get(i).cloned()).collect();
+            PolicySummary::from_results(&per_policy)
+        })
+        .collect();
+
+    println!("\n╔══════════════════════════════════════════════════════════╗");
+    println!("║  Simulation Results (mean ± 95% CI over {:>4} runs)       ║", args.runs);
+    println!("╚══════════════════════════════════════════════════════════╝\n");
+
+    println!("{:<26} {:>20} {:>20} {:>20}",
+        "Policy", "Cost ($)", "Completion (%)", "Preemptions");
+    println!("{}", "-".repeat(89));
+
+    for summary in &summaries {
+        let completion_pct = synkti_simulation::stats::ConfidenceInterval {
+            mean: summary.completion_rate.mean * 100.0,
+            half_width: summary.completion_rate.half_width * 100.0,
+        };
+        println!("{:<26} {:>20} {:>20} {:>20}",
+            summary.policy_name,
+            summary.cost.to_string(),
+            format!("{:.1}", completion_pct),
+            format!("{:.1}", summary.preemptions),
+        );
+    }
+
+    if args.metrics_output.is_some() {
+        println!("\nTime-series metrics are only recorded for single runs");
+    }
+
+    if let Some(ref output_path) = args.output {
+        println!("\nWriting summaries to {}...", output_path);
+        let json = serde_json::to_string_pretty(&summaries).unwrap();
+        fs::write(output_path, json).expect("Failed to write JSON output");
+        println!("  Results saved");
+    }
+}
@@ -0,0 +1,179 @@
+//! Time-series metrics for simulation runs
+//!
+//! Final aggregates in `SimulationResult` show *how much* a policy cost, but not
+//! *when* costs accrued or queues built up. The `MetricsRecorder` samples the
+//! simulator state at a fixed interval so runs can be plotted over time.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+/// Simulator state at a single sample time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSample {
+    /// Sample time (hours)
+    pub time: f64,
+    /// Instances in the `Running` state
+    pub running_instances: usize,
+    /// Total cost booked so far ($)
+    pub total_cost: f64,
+    /// Tasks waiting for placement
+    pub pending_tasks: usize,
+    /// Pending tasks that were displaced by a preemption
+    pub active_preemptions: usize,
+}
+
+/// Recorded time series for a simulation run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSeries {
+    pub samples: Vec<MetricsSample>,
+}
+
+impl MetricsSeries {
+    /// CSV header matching `to_csv` rows
+    pub const CSV_HEADER: &'static str =
+        "time,running_instances,total_cost,pending_tasks,active_preemptions";
+
+    /// Render the series as CSV (with header) for plotting
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(Self::CSV_HEADER);
+        csv.push('\n');
+        for sample in &self.samples {
+            write_csv_row(&mut csv, None, sample);
+        }
+        csv
+    }
+
+    /// Cost series (one value per sample)
+    pub fn cost_series(&self) -> Vec<f64> {
+        self.samples.iter().map(|s| s.total_cost).collect()
+    }
+}
+
+/// Append one CSV row, optionally prefixed by a policy column
+fn write_csv_row(csv: &mut String, policy: Option<&str>, sample: &MetricsSample) {
+    if let Some(policy) = policy {
+        let _ = write!(csv, "{},", policy);
+    }
+    let _ = writeln!(
+        csv,
+        "{:.4},{},{:.4},{},{}",
+        sample.time,
+        sample.running_instances,
+        sample.total_cost,
+        sample.pending_tasks,
+        sample.active_preemptions,
+    );
+}
+
+/// Render several policies' series into a single CSV with a leading `policy` column
+pub fn series_to_csv<'a>(series: impl IntoIterator<Item = (&'a str, &'a MetricsSeries)>) -> String {
+    let mut csv = format!("policy,{}\n", MetricsSeries::CSV_HEADER);
+    for (policy, s) in series {
+        for sample in &s.samples {
+            write_csv_row(&mut csv, Some(policy), sample);
+        }
+    }
+    csv
+}
+
+/// Samples simulator state at a fixed interval
+///
+/// The simulator calls `record_before` before processing each event, so every
+/// sample reflects the state after all events at or before its time.
+#[derive(Debug, Clone)]
+pub struct MetricsRecorder {
+    sample_interval: f64,
+    next_sample: usize,
+    series: MetricsSeries,
+}
+
+impl MetricsRecorder {
+    /// Create a recorder sampling every `sample_interval` hours
+    pub fn new(sample_interval: f64) -> Self {
+        assert!(sample_interval > 0.0, "sample interval must be positive");
+        MetricsRecorder {
+            sample_interval,
+            next_sample: 0,
+            series: MetricsSeries::default(),
+        }
+    }
+
+    /// Emit samples for every sample time strictly before `time`
+    ///
+    /// `snapshot` builds a sample for the current state; it is called once per
+    /// emitted sample with that sample's time.
+    pub fn record_before(&mut self, time: f64, snapshot: impl FnMut(f64) -> MetricsSample) {
+        self.record_while(|t| t < time, snapshot);
+    }
+
+    /// Emit samples for every sample time up to and including `time`
+    pub fn record_until(&mut self, time: f64, snapshot: impl FnMut(f64) -> MetricsSample) {
+        self.record_while(|t| t <= time, snapshot);
+    }
+
+    fn record_while(
+        &mut self,
+        due: impl Fn(f64) -> bool,
+        mut snapshot: impl FnMut(f64) -> MetricsSample,
+    ) {
+        loop {
+            // Multiply rather than accumulate to avoid floating point drift
+            let sample_time = self.next_sample as f64 * self.sample_interval;
+            if !due(sample_time) {
+                break;
+            }
+            self.series.samples.push(snapshot(sample_time));
+            self.next_sample += 1;
+        }
+    }
+
+    /// Finish recording and return the series
+    pub fn finish(self) -> MetricsSeries {
+        self.series
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(time: f64, total_cost: f64) -> MetricsSample {
+        MetricsSample {
+            time,
+            running_instances: 1,
+            total_cost,
+            pending_tasks: 0,
+            active_preemptions: 0,
+        }
+    }
+
+    #[test]
+    fn test_recorder_samples_at_interval() {
+        let mut recorder = MetricsRecorder::new(0.5);
+
+        recorder.record_before(1.5, |t| sample(t, 0.0));
+        recorder.record_before(1.5, |t| sample(t, 1.0)); // No new sample due
+        recorder.record_until(2.0, |t| sample(t, 2.0));
+
+        let series = recorder.finish();
+        let times: Vec<f64> = series.samples.iter().map(|s| s.time).collect();
+        assert_eq!(times, vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+        assert_eq!(series.cost_series(), vec![0.0, 0.0, 0.0, 2.0, 2.0]);
+    }
+
+    #[test]
+    fn test_csv_output() {
+        let series = MetricsSeries {
+            samples: vec![sample(0.0, 0.0), sample(1.0, 0.25)],
+        };
+
+        let csv = series.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], MetricsSeries::CSV_HEADER);
+        assert_eq!(lines[2], "1.0000,1,0.2500,0,0");
+
+        let combined = series_to_csv([("Greedy-Optimal", &series)]);
+        assert!(combined.starts_with("policy,time,"));
+        assert!(combined.contains("Greedy-Optimal,1.0000,1,0.2500,0,0"));
+    }
+}
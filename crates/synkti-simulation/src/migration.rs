@@ -2,10 +2,52 @@
 //!
 //! When spot instances are preempted, we need to migrate running tasks to other instances.
 //! This module implements optimal assignment to minimize total migration cost.
+//!
+//! Two cost models are available: KV-cache transfer time (`migration_cost`),
+//! and stateless failover (`stateless_migration_cost`), where nothing is
+//! transferred and the cost is reloading the model on a cold target.
 
-use crate::types::{Instance, InstanceType, Task};
+use crate::types::{Instance, Task};
 use pathfinding::matrix::Matrix;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// Integer cost units per second passed to the Kuhn-Munkres solver
+///
+/// The solver works on integer weights, so costs are quantized to this
+/// resolution (1 µs). Transfers that differ by less than this are ties.
+const COST_SCALE: f64 = 1_000_000.0;
+
+/// Integer cost for infeasible assignments (~11.6 days at `COST_SCALE`)
+///
+/// Must dominate the sum of all feasible costs so the solver never trades a
+/// feasible assignment for an infeasible one.
+const INFEASIBLE_COST: i64 = 1_000_000_000_000;
+
+/// Estimated seconds to load the model on a cold target instance
+pub const DEFAULT_MODEL_LOAD_SECONDS: f64 = 300.0;
+
+/// Seconds to re-dispatch a request to a target that already serves the model
+pub const WARM_REDISPATCH_SECONDS: f64 = 1.0;
+
+/// Summary of one migration plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationPlanSummary {
+    /// Total transfer time across assigned tasks (seconds)
+    pub total_cost: f64,
+    /// Number of tasks that received an instance
+    pub assigned: usize,
+    /// Slowest single task transfer (seconds)
+    pub max_transfer_time: f64,
+}
+
+/// Head-to-head comparison of naive first-fit and optimal Kuhn-Munkres plans
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationComparison {
+    pub naive: MigrationPlanSummary,
+    pub optimal: MigrationPlanSummary,
+    /// Reduction in total cost from naive to optimal (%)
+    pub improvement_percent: f64,
+}
 
 /// Plans optimal task-to-instance migration using the Kuhn-Munkres algorithm
 pub struct MigrationPlanner;
@@ -34,9 +76,27 @@ impl MigrationPlanner {
         // network_bandwidth_gbps * 1000 / 8 = MB/s
         // transfer_time = size_mb / (bandwidth_MB_s)
         let bandwidth_mb_per_sec = instance.network_bandwidth_gbps * 125.0; // Gbps to MB/s
-        let transfer_time_sec = task.kv_cache_size_mb / bandwidth_mb_per_sec;
+        task.kv_cache_size_mb / bandwidth_mb_per_sec
+    }
+
+    /// Calculate stateless failover cost for a single task to a single instance
+    ///
+    /// With stateless failover no KV cache is transferred; the request is
+    /// replayed on the target. A warm target (model already loaded) costs a
+    /// near-zero re-dispatch, a cold one costs the model load.
+    ///
+    /// # Returns
+    /// Migration cost in seconds, or f64::INFINITY if the task doesn't fit
+    pub fn stateless_migration_cost(task: &Task, instance: &Instance, is_warm: bool) -> f64 {
+        if !task.can_fit_in_memory(instance.available_memory_mb()) {
+            return f64::INFINITY; // Infeasible assignment
+        }
 
-        transfer_time_sec
+        if is_warm {
+            WARM_REDISPATCH_SECONDS
+        } else {
+            DEFAULT_MODEL_LOAD_SECONDS
+        }
     }
 
     /// Build cost matrix for all task-instance pairs
@@ -88,7 +148,44 @@ impl MigrationPlanner {
 
         // Build cost matrix
         let cost_matrix = Self::build_cost_matrix(displaced_tasks, available_instances);
+        Self::solve_assignment(displaced_tasks, available_instances, &cost_matrix)
+    }
+
+    /// Plan optimal stateless failover using Kuhn-Munkres algorithm
+    ///
+    /// Same matching as `plan_optimal_migration`, but costed with
+    /// `stateless_migration_cost`: tasks go to instances in `warm_instances`
+    /// (model already loaded) before any cold instance.
+    pub fn plan_stateless_migration(
+        displaced_tasks: &[Task],
+        available_instances: &[Instance],
+        warm_instances: &HashSet<u64>,
+    ) -> HashMap<u64, u64> {
+        if displaced_tasks.is_empty() || available_instances.is_empty() {
+            return HashMap::new();
+        }
 
+        let cost_matrix: Vec<Vec<f64>> = displaced_tasks
+            .iter()
+            .map(|task| {
+                available_instances
+                    .iter()
+                    .map(|instance| {
+                        let is_warm = warm_instances.contains(&instance.id);
+                        Self::stateless_migration_cost(task, instance, is_warm)
+                    })
+                    .collect()
+            })
+            .collect();
+        Self::solve_assignment(displaced_tasks, available_instances, &cost_matrix)
+    }
+
+    /// Find the minimum-cost matching for a task x instance cost matrix
+    fn solve_assignment(
+        displaced_tasks: &[Task],
+        available_instances: &[Instance],
+        cost_matrix: &[Vec<f64>],
+    ) -> HashMap<u64, u64> {
         // Handle case where we have more tasks than instances
         // We need a square matrix for KM algorithm, so we'll pad with dummy instances
         let num_tasks = displaced_tasks.len();
@@ -103,15 +200,15 @@ impl MigrationPlanner {
             }
         }
 
-        // Convert to integer costs for pathfinding crate (multiply by 1000 for precision)
+        // Convert to integer costs for pathfinding crate
         let int_costs: Vec<i64> = square_matrix
             .iter()
             .flat_map(|row| {
                 row.iter().map(|&cost| {
                     if cost.is_infinite() {
-                        1_000_000_000 // Use large value for infeasible (1 billion, much larger than realistic costs)
+                        INFEASIBLE_COST
                     } else {
-                        (cost * 1000.0) as i64 // Preserve 3 decimal places
+                        (cost * COST_SCALE).round() as i64
                     }
                 })
             })
@@ -120,8 +217,8 @@ impl MigrationPlanner {
         // Create Matrix from flattened costs
         let matrix = Matrix::from_vec(matrix_size, matrix_size, int_costs).unwrap();
 
-        // Run Kuhn-Munkres algorithm
-        let (_total_cost, assignment) = pathfinding::kuhn_munkres::kuhn_munkres(&matrix);
+        // Run Kuhn-Munkres for the minimum-cost assignment
+        let (_total_cost, assignment) = pathfinding::kuhn_munkres::kuhn_munkres_min(&matrix);
 
         // Convert assignment to task_id -> instance_id map
         let mut migration_plan = HashMap::new();
@@ -225,11 +322,57 @@ impl MigrationPlanner {
 
         total_cost
     }
+
+    /// Summarize an assignment: total cost, assigned count and slowest transfer
+    fn summarize(
+        tasks: &[Task],
+        instances: &[Instance],
+        assignment: &HashMap<u64, u64>,
+    ) -> MigrationPlanSummary {
+        let max_transfer_time = assignment
+            .iter()
+            .filter_map(|(task_id, instance_id)| {
+                let task = tasks.iter().find(|t| t.id == *task_id)?;
+                let instance = instances.iter().find(|i| i.id == *instance_id)?;
+                Some(Self::migration_cost(task, instance))
+            })
+            .filter(|cost| cost.is_finite())
+            .fold(0.0, f64::max);
+
+        MigrationPlanSummary {
+            total_cost: Self::calculate_total_cost(tasks, instances, assignment),
+            assigned: assignment.len(),
+            max_transfer_time,
+        }
+    }
+
+    /// Compare naive first-fit and optimal Kuhn-Munkres plans for the same displacement
+    ///
+    /// Note that the optimal plan may assign fewer tasks than naive when naive
+    /// packs several tasks onto one instance; compare `assigned` alongside cost.
+    pub fn compare_strategies(tasks: &[Task], instances: &[Instance]) -> MigrationComparison {
+        let naive = Self::summarize(tasks, instances, &Self::plan_naive_migration(tasks, instances));
+        let optimal =
+            Self::summarize(tasks, instances, &Self::plan_optimal_migration(tasks, instances));
+
+        let improvement_percent = if naive.total_cost > 0.0 {
+            (naive.total_cost - optimal.total_cost) / naive.total_cost * 100.0
+        } else {
+            0.0
+        };
+
+        MigrationComparison {
+            naive,
+            optimal,
+            improvement_percent,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::InstanceType;
 
     #[test]
     fn test_migration_cost_calculation() {
@@ -297,6 +440,27 @@ mod tests {
         assert!(assignment.contains_key(&2), "Task 2 should be assigned");
     }
 
+    #[test]
+    fn test_optimal_migration_minimizes_cost() {
+        let small = Task::new(1, 0.0, 5.0); // 1 GB cache
+        let large = Task::new(2, 0.0, 40.0); // 8 GB cache
+
+        let fast = Instance::new(100, InstanceType::Spot, 0.30, 0.0); // 10 Gbps
+        let mut slow = Instance::new(101, InstanceType::Spot, 0.30, 0.0);
+        slow.network_bandwidth_gbps = 1.0;
+
+        let tasks = vec![small, large];
+        let instances = vec![fast, slow];
+        let assignment = MigrationPlanner::plan_optimal_migration(&tasks, &instances);
+
+        // The large task takes the fast link: 1000/125 + 8000/1250 = 14.4s,
+        // not 1000/1250 + 8000/125 = 64.8s
+        assert_eq!(assignment.get(&1), Some(&101));
+        assert_eq!(assignment.get(&2), Some(&100));
+        let total = MigrationPlanner::calculate_total_cost(&tasks, &instances, &assignment);
+        assert!((total - 14.4).abs() < 0.01, "total cost was {}", total);
+    }
+
     #[test]
     fn test_migration_filters_too_large_tasks() {
         // Create task that's too large for any instance
@@ -428,6 +592,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_compare_strategies_asymmetric_bandwidth() {
+        let small = Task::new(1, 0.0, 5.0); // 1 GB cache
+        let large = Task::new(2, 0.0, 40.0); // 8 GB cache
+
+        // The fast instance has room for only one of the two tasks. First-fit
+        // gives it the small task, leaving the large one on the slow link.
+        let mut fast = Instance::new(100, InstanceType::Spot, 0.30, 0.0); // 10 Gbps
+        fast.gpu_memory_used_mb = 15_800.0;
+        let mut slow = Instance::new(101, InstanceType::Spot, 0.30, 0.0);
+        slow.network_bandwidth_gbps = 1.0;
+
+        let comparison = MigrationPlanner::compare_strategies(&[small, large], &[fast, slow]);
+
+        // Naive: 1000/1250 + 8000/125 = 64.8s; optimal: 1000/125 + 8000/1250 = 14.4s
+        assert_eq!(comparison.naive.assigned, 2);
+        assert_eq!(comparison.optimal.assigned, 2);
+        assert!((comparison.naive.total_cost - 64.8).abs() < 0.01);
+        assert!((comparison.optimal.total_cost - 14.4).abs() < 0.01);
+        assert!((comparison.naive.max_transfer_time - 64.0).abs() < 0.01);
+        assert!((comparison.optimal.max_transfer_time - 8.0).abs() < 0.01);
+        assert!(comparison.optimal.total_cost < comparison.naive.total_cost);
+        assert!((comparison.improvement_percent - 77.78).abs() < 0.01);
+    }
+
+    fn instance_with_bandwidth(id: u64, bandwidth_gbps: f64) -> Instance {
+        let mut instance = Instance::new(id, InstanceType::Spot, 0.30, 0.0);
+        instance.network_bandwidth_gbps = bandwidth_gbps;
+        instance
+    }
+
+    #[test]
+    fn test_optimal_migration_steers_largest_task_to_fastest_network() {
+        let tasks = vec![
+            Task::new(1, 0.0, 5.0),  // 1 GB cache
+            Task::new(2, 0.0, 40.0), // 8 GB cache
+            Task::new(3, 0.0, 20.0), // 4 GB cache
+        ];
+        let instances = vec![
+            instance_with_bandwidth(100, 10.0),
+            instance_with_bandwidth(101, 25.0),
+            instance_with_bandwidth(102, 10.0),
+        ];
+
+        let assignment = MigrationPlanner::plan_optimal_migration(&tasks, &instances);
+
+        assert_eq!(assignment.len(), 3);
+        assert_eq!(assignment[&2], 101, "Largest task should get the 25 Gbps instance");
+
+        // 8000/3125 + 4000/1250 + 1000/1250 = 2.56 + 3.2 + 0.8
+        let cost = MigrationPlanner::calculate_total_cost(&tasks, &instances, &assignment);
+        assert!((cost - 6.56).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_optimal_migration_resolves_sub_millisecond_differences() {
+        // Tiny caches: every transfer is well under a millisecond
+        let mut small = Task::new(1, 0.0, 1.0);
+        small.kv_cache_size_mb = 0.5;
+        let mut large = Task::new(2, 0.0, 1.0);
+        large.kv_cache_size_mb = 1.0;
+
+        let tasks = vec![small, large];
+        let instances = vec![instance_with_bandwidth(100, 25.0), instance_with_bandwidth(101, 10.0)];
+
+        let assignment = MigrationPlanner::plan_optimal_migration(&tasks, &instances);
+
+        assert_eq!(assignment[&2], 100);
+        assert_eq!(assignment[&1], 101);
+    }
+
     #[test]
     fn test_naive_migration_empty_inputs() {
         let tasks = vec![];
@@ -440,4 +675,38 @@ mod tests {
         let assignment = MigrationPlanner::plan_naive_migration(&tasks, &instances);
         assert!(assignment.is_empty());
     }
+
+    #[test]
+    fn test_stateless_cost_warm_vs_cold() {
+        let task = Task::new(1, 0.0, 10.0);
+        let instance = Instance::new(100, InstanceType::Spot, 0.30, 0.0);
+
+        let warm = MigrationPlanner::stateless_migration_cost(&task, &instance, true);
+        let cold = MigrationPlanner::stateless_migration_cost(&task, &instance, false);
+
+        assert_eq!(warm, WARM_REDISPATCH_SECONDS);
+        assert_eq!(cold, DEFAULT_MODEL_LOAD_SECONDS);
+        assert!(warm < cold);
+
+        let mut huge = Task::new(2, 0.0, 10.0);
+        huge.kv_cache_size_mb = 100_000.0;
+        assert!(MigrationPlanner::stateless_migration_cost(&huge, &instance, true).is_infinite());
+    }
+
+    #[test]
+    fn test_stateless_migration_prefers_warm_targets() {
+        let tasks = vec![Task::new(1, 0.0, 5.0), Task::new(2, 0.0, 40.0)];
+        // The cold instance has the faster network, which doesn't matter here
+        let instances = vec![
+            instance_with_bandwidth(100, 25.0),
+            instance_with_bandwidth(101, 10.0),
+            instance_with_bandwidth(102, 10.0),
+        ];
+        let warm = HashSet::from([101, 102]);
+
+        let assignment = MigrationPlanner::plan_stateless_migration(&tasks, &instances, &warm);
+
+        assert_eq!(assignment.len(), 2);
+        assert!(assignment.values().all(|id| warm.contains(id)));
+    }
 }
@@ -3,9 +3,13 @@
 //! Implements multiple policies to compare:
 //! - Greedy: Always use cheapest (spot) instances
 //! - OnDemand Fallback: Use spot, fallback to on-demand on preemption
-//! - (Future) Uniform Progress: Deadline-aware scheduling from "Can't Be Late" paper
+//! - Deadline Aware: Spot while there is slack, on-demand as the deadline nears
+//!   (in the spirit of the "Can't Be Late" paper)
+//! - Warm Pool: Spot, with K idle spot instances kept booted for failover
+//! - Rate Aware: Spot, converting to on-demand while preemptions/hour
+//!   stay above a threshold
 
-use crate::types::{Instance, InstanceState, InstanceType, Task};
+use crate::types::{Instance, InstanceType, Task};
 
 /// Scheduling policy trait
 pub trait SchedulingPolicy {
@@ -15,6 +19,24 @@ pub trait SchedulingPolicy {
     /// Handle preemption event
     fn handle_preemption(&mut self, task: &mut Task, instance: &Instance);
 
+    /// Observe a preempted spot instance, once per instance
+    ///
+    /// Called whether or not the instance was running tasks (idle instances
+    /// are preempted too); the default ignores it.
+    fn observe_preemption(&mut self, _instance: &Instance) {}
+
+    /// Observe the current simulation time before a placement decision
+    ///
+    /// Time-aware policies (e.g. deadline-aware) override this; the default ignores it.
+    fn observe_time(&mut self, _current_time: f64) {}
+
+    /// Number of idle, pre-booted spot instances the simulator keeps for failover
+    ///
+    /// The default of zero disables the warm pool.
+    fn warm_pool_size(&self) -> usize {
+        0
+    }
+
     /// Get policy name
     fn name(&self) -> &str;
 }
@@ -32,6 +54,12 @@ impl GreedyPolicy {
     }
 }
 
+impl Default for GreedyPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SchedulingPolicy for GreedyPolicy {
     fn select_instance_type(&mut self, _task: &Task, _spot_price: f64, _on_demand_price: f64) -> InstanceType {
         // Always choose spot (cheapest)
@@ -109,6 +137,12 @@ impl OnDemandOnlyPolicy {
     }
 }
 
+impl Default for OnDemandOnlyPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SchedulingPolicy for OnDemandOnlyPolicy {
     fn select_instance_type(&mut self, _task: &Task, _spot_price: f64, _on_demand_price: f64) -> InstanceType {
         InstanceType::OnDemand
@@ -124,9 +158,185 @@ impl SchedulingPolicy for OnDemandOnlyPolicy {
     }
 }
 
+/// Deadline-aware policy: spot for tasks with slack, on-demand near the deadline
+///
+/// A spot preemption can cost a task its progress, so spot is only safe while
+/// the task could still absorb a restart. Once the slack (time to deadline minus
+/// remaining work) drops below `slack_threshold` hours, the task is placed on a
+/// guaranteed on-demand instance. Tasks without a deadline always use spot.
+pub struct DeadlineAwarePolicy {
+    pub total_preemptions: usize,
+    pub on_demand_placements: usize,
+    /// Minimum slack (hours) required to risk a spot instance
+    slack_threshold: f64,
+    current_time: f64,
+}
+
+impl DeadlineAwarePolicy {
+    pub fn new(slack_threshold: f64) -> Self {
+        DeadlineAwarePolicy {
+            total_preemptions: 0,
+            on_demand_placements: 0,
+            slack_threshold,
+            current_time: 0.0,
+        }
+    }
+}
+
+impl SchedulingPolicy for DeadlineAwarePolicy {
+    fn select_instance_type(&mut self, task: &Task, _spot_price: f64, _on_demand_price: f64) -> InstanceType {
+        match task.slack(self.current_time) {
+            Some(slack) if slack < self.slack_threshold => {
+                // Not enough slack to survive a preemption
+                self.on_demand_placements += 1;
+                InstanceType::OnDemand
+            }
+            _ => InstanceType::Spot,
+        }
+    }
+
+    fn handle_preemption(&mut self, task: &mut Task, _instance: &Instance) {
+        self.total_preemptions += 1;
+        // Task will be rescheduled; slack is re-evaluated at placement
+        task.assigned_instance = None;
+    }
+
+    fn observe_time(&mut self, current_time: f64) {
+        self.current_time = current_time;
+    }
+
+    fn name(&self) -> &str {
+        "DeadlineAware"
+    }
+}
+
+/// Warm-pool policy: spot instances, plus K idle spot instances kept warm
+///
+/// Preempted tasks move onto a warm instance immediately instead of waiting
+/// for a cold launch (boot + model load). The pool is refilled after each
+/// claim; its idle time is billed and reported separately so the saved
+/// failover latency can be weighed against the extra cost.
+pub struct WarmPoolPolicy {
+    pub total_preemptions: usize,
+    pool_size: usize,
+}
+
+impl WarmPoolPolicy {
+    pub fn new(pool_size: usize) -> Self {
+        WarmPoolPolicy {
+            total_preemptions: 0,
+            pool_size,
+        }
+    }
+}
+
+impl SchedulingPolicy for WarmPoolPolicy {
+    fn select_instance_type(&mut self, _task: &Task, _spot_price: f64, _on_demand_price: f64) -> InstanceType {
+        InstanceType::Spot
+    }
+
+    fn handle_preemption(&mut self, task: &mut Task, _instance: &Instance) {
+        self.total_preemptions += 1;
+        // Task will be moved to a warm instance if one is ready
+        task.assigned_instance = None;
+    }
+
+    fn warm_pool_size(&self) -> usize {
+        self.pool_size
+    }
+
+    fn name(&self) -> &str {
+        "WarmPool"
+    }
+}
+
+/// Rate-aware policy: on-demand while the spot market is unstable
+///
+/// Tracks fleet-wide instance preemptions over a sliding window. While the rate
+/// (preemptions per hour) exceeds `threshold_per_hour`, new instances are
+/// launched on-demand; once it falls back, spot is used again. The extra
+/// hourly cost of each conversion is accumulated in `on_demand_premium`.
+pub struct RateAwarePolicy {
+    pub total_preemptions: usize,
+    pub on_demand_conversions: usize,
+    /// Sum of (on-demand - spot) hourly price over all conversions ($/hr)
+    pub on_demand_premium: f64,
+    threshold_per_hour: f64,
+    window_hours: f64,
+    /// (instance id, preemption time) of preempted instances in the window
+    preemptions: std::collections::VecDeque<(u64, f64)>,
+    current_time: f64,
+}
+
+impl RateAwarePolicy {
+    /// # Panics
+    ///
+    /// If `window_hours` is not positive.
+    pub fn new(threshold_per_hour: f64, window_hours: f64) -> Self {
+        assert!(
+            window_hours > 0.0,
+            "RateAwarePolicy window must be positive, got {}h",
+            window_hours
+        );
+        RateAwarePolicy {
+            total_preemptions: 0,
+            on_demand_conversions: 0,
+            on_demand_premium: 0.0,
+            threshold_per_hour,
+            window_hours,
+            preemptions: std::collections::VecDeque::new(),
+            current_time: 0.0,
+        }
+    }
+
+    /// Preemptions per hour over the window ending at the current time
+    pub fn interruption_rate(&self) -> f64 {
+        let since = self.current_time - self.window_hours;
+        let recent = self.preemptions.iter().filter(|&&(_, t)| t > since).count();
+        recent as f64 / self.window_hours
+    }
+}
+
+impl SchedulingPolicy for RateAwarePolicy {
+    fn select_instance_type(&mut self, _task: &Task, spot_price: f64, on_demand_price: f64) -> InstanceType {
+        if self.interruption_rate() > self.threshold_per_hour {
+            self.on_demand_conversions += 1;
+            self.on_demand_premium += on_demand_price - spot_price;
+            InstanceType::OnDemand
+        } else {
+            InstanceType::Spot
+        }
+    }
+
+    fn handle_preemption(&mut self, task: &mut Task, _instance: &Instance) {
+        self.total_preemptions += 1;
+        task.assigned_instance = None;
+    }
+
+    fn observe_preemption(&mut self, instance: &Instance) {
+        if !self.preemptions.iter().any(|&(id, _)| id == instance.id) {
+            let time = instance.end_time.unwrap_or(self.current_time);
+            self.preemptions.push_back((instance.id, time));
+        }
+    }
+
+    fn observe_time(&mut self, current_time: f64) {
+        self.current_time = current_time;
+        let since = current_time - self.window_hours;
+        while self.preemptions.front().is_some_and(|&(_, t)| t <= since) {
+            self.preemptions.pop_front();
+        }
+    }
+
+    fn name(&self) -> &str {
+        "RateAware"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::InstanceState;
 
     #[test]
     fn test_greedy_policy() {
@@ -176,4 +386,103 @@ mod tests {
         let instance_type = policy.select_instance_type(&task, 0.30, 1.00);
         assert_eq!(instance_type, InstanceType::OnDemand);
     }
+
+    #[test]
+    fn test_deadline_aware_tight_deadline_uses_on_demand() {
+        let mut policy = DeadlineAwarePolicy::new(2.0);
+
+        // 10h of work due in 11h: only 1h slack
+        let tight = Task::new(1, 0.0, 10.0).with_deadline(11.0);
+        assert_eq!(policy.select_instance_type(&tight, 0.30, 1.00), InstanceType::OnDemand);
+
+        // 10h of work due in 30h: plenty of slack
+        let slack = Task::new(2, 0.0, 10.0).with_deadline(30.0);
+        assert_eq!(policy.select_instance_type(&slack, 0.30, 1.00), InstanceType::Spot);
+
+        // No deadline: always spot
+        let best_effort = Task::new(3, 0.0, 10.0);
+        assert_eq!(policy.select_instance_type(&best_effort, 0.30, 1.00), InstanceType::Spot);
+
+        assert_eq!(policy.on_demand_placements, 1);
+    }
+
+    #[test]
+    fn test_deadline_aware_slack_shrinks_over_time() {
+        let mut policy = DeadlineAwarePolicy::new(2.0);
+        let task = Task::new(1, 0.0, 10.0).with_deadline(20.0);
+
+        policy.observe_time(0.0);
+        assert_eq!(policy.select_instance_type(&task, 0.30, 1.00), InstanceType::Spot);
+
+        // After a preemption at t=9 the same work now has only 1h slack
+        policy.observe_time(9.0);
+        assert_eq!(policy.select_instance_type(&task, 0.30, 1.00), InstanceType::OnDemand);
+    }
+
+    #[test]
+    fn test_interruption_rate_threshold_crossing() {
+        // Convert above 1 preemption/hour over a 2-hour window
+        let mut policy = RateAwarePolicy::new(1.0, 2.0);
+        let task = Task::new(1, 0.0, 10.0);
+        let preempt = |policy: &mut RateAwarePolicy, id: u64, time: f64| {
+            let mut instance = Instance::new(id, InstanceType::Spot, 0.30, 0.0);
+            instance.state = InstanceState::Preempted;
+            instance.end_time = Some(time);
+            policy.observe_preemption(&instance);
+        };
+
+        // Synthetic series: calm, then a burst of preemptions, then calm again
+        let decide = |policy: &mut RateAwarePolicy, time: f64| {
+            policy.observe_time(time);
+            policy.select_instance_type(&task, 0.30, 1.00)
+        };
+        let mut decisions = Vec::new();
+        preempt(&mut policy, 1, 0.5);
+        decisions.push(decide(&mut policy, 1.0));
+        for (id, time) in [(2, 3.2), (3, 3.5), (4, 3.8)] {
+            preempt(&mut policy, id, time);
+        }
+        for time in [4.0, 5.0, 6.0] {
+            decisions.push(decide(&mut policy, time));
+        }
+
+        // t=1: 1 in (-1, 1] = 0.5/h; t=4: 3 in (2, 4] = 1.5/h;
+        // t=5: still 3 in (3, 5]; t=6: none in (4, 6]
+        assert_eq!(
+            decisions,
+            [InstanceType::Spot, InstanceType::OnDemand, InstanceType::OnDemand, InstanceType::Spot]
+        );
+        assert_eq!(policy.on_demand_conversions, 2);
+        assert!((policy.on_demand_premium - 1.40).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_interruption_rate_counts_instances() {
+        let mut policy = RateAwarePolicy::new(1.0, 1.0);
+        let preempted = |id: u64| {
+            let mut instance = Instance::new(id, InstanceType::Spot, 0.30, 0.0);
+            instance.state = InstanceState::Preempted;
+            instance.end_time = Some(2.0);
+            instance
+        };
+
+        // Instance 1 displaced two tasks; instance 2 was idle
+        policy.observe_preemption(&preempted(1));
+        for task_id in [1, 2] {
+            policy.handle_preemption(&mut Task::new(task_id, 0.0, 5.0), &preempted(1));
+        }
+        policy.observe_preemption(&preempted(2));
+        // A repeated report of the same instance isn't counted again
+        policy.observe_preemption(&preempted(1));
+
+        policy.observe_time(2.5);
+        assert_eq!(policy.total_preemptions, 2);
+        assert_eq!(policy.interruption_rate(), 2.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "window must be positive")]
+    fn test_rate_aware_rejects_empty_window() {
+        RateAwarePolicy::new(1.0, 0.0);
+    }
 }
@@ -3,14 +3,17 @@
 //! Simulates task scheduling, instance management, and preemption handling
 //! over a configurable time period to compare scheduling policies.
 
-use std::collections::{BinaryHeap, HashMap};
-use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::cmp::{Ordering, Reverse};
 
 use crate::types::{Event, Instance, InstanceState, InstanceType, Task, SpotPrice};
 use crate::policies::SchedulingPolicy;
 use crate::migration::MigrationPlanner;
 use crate::checkpoint::CheckpointPlanner;
+use crate::metrics::{MetricsRecorder, MetricsSample, MetricsSeries};
 
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
 /// Result of a simulation run
@@ -26,6 +29,24 @@ pub struct SimulationResult {
     pub checkpoints_attempted: usize,
     pub checkpoints_successful: usize,
     pub total_time_saved_hours: f64,
+    /// Tasks that completed after (or were unfinished at) their deadline
+    #[serde(default)]
+    pub deadline_misses: usize,
+    /// Cost of warm-pool instances sitting idle (included in `total_cost`)
+    #[serde(default)]
+    pub warm_pool_idle_cost: f64,
+    /// Preempted tasks moved onto a warm-pool instance
+    #[serde(default)]
+    pub warm_failovers: usize,
+    /// Preempted tasks that had to wait for a cold instance launch
+    #[serde(default)]
+    pub cold_failover_launches: usize,
+    /// Boot + model load time avoided by warm failovers (hours, summed over tasks)
+    #[serde(default)]
+    pub failover_latency_saved_hours: f64,
+    /// Time-series metrics (only when enabled via `Simulator::with_metrics`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<MetricsSeries>,
 }
 
 /// Timed event wrapper for priority queue ordering
@@ -61,15 +82,21 @@ impl PartialEq for TimedEvent {
 pub struct Simulator {
     current_time: f64,
     event_queue: BinaryHeap<TimedEvent>,
-    instances: HashMap<u64, Instance>,
-    tasks: HashMap<u64, Task>,
+    // Ordered maps keep iteration (and therefore seeded runs) deterministic
+    instances: BTreeMap<u64, Instance>,
+    tasks: BTreeMap<u64, Task>,
     pending_tasks: Vec<u64>,
+    awaiting_boot: HashMap<u64, u64>, // task_id -> booting instance launched for it
+    warm_pool: BTreeMap<u64, Option<f64>>, // idle instance_id -> time it became ready
     policy: Box<dyn SchedulingPolicy>,
     spot_prices: Vec<SpotPrice>,
+    rng: StdRng,
 
     // Configuration
     on_demand_price: f64,
     use_optimal_migration: bool, // If true, use KM algorithm; if false, use naive greedy
+    boot_latency: f64,           // Hours from launch decision until the instance is up
+    model_load_latency: f64,     // Hours to load the model once booted
 
     // ID generators
     next_instance_id: u64,
@@ -81,6 +108,11 @@ pub struct Simulator {
     checkpoints_attempted: usize,
     checkpoints_successful: usize,
     total_time_saved_hours: f64,
+    warm_pool_idle_cost: f64,
+    warm_failovers: usize,
+    cold_failover_launches: usize,
+    failover_latency_saved_hours: f64,
+    metrics: Option<MetricsRecorder>,
 }
 
 impl Simulator {
@@ -94,13 +126,18 @@ impl Simulator {
         Simulator {
             current_time: 0.0,
             event_queue: BinaryHeap::new(),
-            instances: HashMap::new(),
-            tasks: HashMap::new(),
+            instances: BTreeMap::new(),
+            tasks: BTreeMap::new(),
             pending_tasks: Vec::new(),
+            awaiting_boot: HashMap::new(),
+            warm_pool: BTreeMap::new(),
             policy,
             spot_prices,
+            rng: StdRng::from_entropy(),
             on_demand_price,
             use_optimal_migration,
+            boot_latency: 0.0,
+            model_load_latency: 0.0,
             next_instance_id: 0,
             total_cost: 0.0,
             total_preemptions: 0,
@@ -108,9 +145,41 @@ impl Simulator {
             checkpoints_attempted: 0,
             checkpoints_successful: 0,
             total_time_saved_hours: 0.0,
+            warm_pool_idle_cost: 0.0,
+            warm_failovers: 0,
+            cold_failover_launches: 0,
+            failover_latency_saved_hours: 0.0,
+            metrics: None,
         }
     }
 
+    /// Record time-series metrics every `sample_interval` hours
+    ///
+    /// The series is returned in `SimulationResult::metrics`.
+    pub fn with_metrics(mut self, sample_interval: f64) -> Self {
+        self.metrics = Some(MetricsRecorder::new(sample_interval));
+        self
+    }
+
+    /// Drive preemption sampling from `rng` instead of OS entropy
+    ///
+    /// Two simulators given identically seeded RNGs and the same tasks and
+    /// prices produce identical results.
+    pub fn with_rng(mut self, rng: StdRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Delay instance availability by `boot_latency + model_load_latency` hours
+    ///
+    /// Launched instances stay `Booting` (and are billed) until the delay
+    /// elapses; no tasks are assigned to them before then. Defaults to zero.
+    pub fn with_launch_latency(mut self, boot_latency: f64, model_load_latency: f64) -> Self {
+        self.boot_latency = boot_latency;
+        self.model_load_latency = model_load_latency;
+        self
+    }
+
     /// Add a task to the simulation
     pub fn add_task(&mut self, task: Task) {
         let task_id = task.id;
@@ -127,16 +196,72 @@ impl Simulator {
 
     /// Run the simulation for the specified duration
     pub fn run(&mut self, duration: f64) -> SimulationResult {
+        self.replenish_warm_pool();
+
         while let Some(timed_event) = self.event_queue.pop() {
             if timed_event.time > duration {
                 break;
             }
 
+            // Samples at exactly an event's time are taken after that event runs
+            if let Some(recorder) = self.metrics.as_mut() {
+                let snapshot = Self::metrics_snapshot(
+                    &self.instances,
+                    &self.tasks,
+                    &self.pending_tasks,
+                    self.total_cost,
+                );
+                recorder.record_before(timed_event.time, snapshot);
+            }
+
             self.current_time = timed_event.time;
             self.process_event(timed_event.event);
         }
 
-        self.collect_results()
+        // Bill warm instances still idle at the end of the run
+        let idle: Vec<(u64, f64)> = self
+            .warm_pool
+            .iter()
+            .filter_map(|(id, ready)| ready.map(|ready| (*id, ready)))
+            .collect();
+        for (instance_id, ready_time) in idle {
+            self.bill_warm_idle(instance_id, ready_time, duration);
+            self.warm_pool.insert(instance_id, Some(duration));
+        }
+
+        if let Some(recorder) = self.metrics.as_mut() {
+            let snapshot = Self::metrics_snapshot(
+                &self.instances,
+                &self.tasks,
+                &self.pending_tasks,
+                self.total_cost,
+            );
+            recorder.record_until(duration, snapshot);
+        }
+
+        self.collect_results(duration)
+    }
+
+    /// Build a metrics snapshot closure over the current state
+    fn metrics_snapshot<'a>(
+        instances: &'a BTreeMap<u64, Instance>,
+        tasks: &'a BTreeMap<u64, Task>,
+        pending_tasks: &'a [u64],
+        total_cost: f64,
+    ) -> impl FnMut(f64) -> MetricsSample + 'a {
+        move |time| MetricsSample {
+            time,
+            running_instances: instances
+                .values()
+                .filter(|i| i.state == InstanceState::Running)
+                .count(),
+            total_cost,
+            pending_tasks: pending_tasks.len(),
+            active_preemptions: pending_tasks
+                .iter()
+                .filter(|id| tasks.get(id).is_some_and(|t| t.preemption_count > 0))
+                .count(),
+        }
     }
 
     /// Process a single event
@@ -165,42 +290,61 @@ impl Simulator {
         let mut assigned_tasks = Vec::new();
         let mut tasks_needing_instances = Vec::new();
 
+        // Higher-priority tasks claim capacity first (arrival order within a tier)
+        let mut pending = self.pending_tasks.clone();
+        pending.sort_by_key(|id| Reverse(self.tasks.get(id).map_or(0, |t| t.priority)));
+
         // First pass: collect information without holding borrows
-        for &task_id in &self.pending_tasks {
-            if let Some(task) = self.tasks.get(&task_id) {
-                // Find an instance with available memory
-                let instance_id = self.find_available_instance(task);
-
-                if instance_id.is_some() {
-                    assigned_tasks.push((task_id, instance_id.unwrap()));
-                } else {
-                    // No available instance, need to launch one
-                    tasks_needing_instances.push(task_id);
-                }
+        for task_id in pending {
+            let Some(task) = self.tasks.get(&task_id) else {
+                continue;
+            };
+            let displaced = task.preemption_count > 0;
+
+            // Find an instance with available memory
+            if let Some(instance_id) = self.find_available_instance(task) {
+                assigned_tasks.push((task_id, instance_id));
+            } else if self.awaiting_boot.contains_key(&task_id) {
+                // Already waiting for its own instance to boot
+            } else if let Some(instance_id) = displaced.then(|| self.find_warm_instance(task)).flatten() {
+                // Failover onto a pre-booted instance
+                assigned_tasks.push((task_id, instance_id));
+            } else {
+                // No available instance, need to launch one
+                tasks_needing_instances.push(task_id);
             }
         }
 
         // Second pass: perform assignments
-        for (task_id, inst_id) in assigned_tasks.iter() {
-            if let Some(task) = self.tasks.get_mut(task_id) {
-                if let Some(instance) = self.instances.get_mut(inst_id) {
-                    if instance.assign_task(task) {
-                        task.assigned_instance = Some(*inst_id);
-                        task.start_time = Some(self.current_time);
-
-                        // Schedule completion event
-                        let completion_time = self.current_time + task.remaining_time;
-                        self.event_queue.push(TimedEvent {
-                            time: completion_time,
-                            event: Event::TaskCompletion {
-                                task_id: *task_id,
-                                time: completion_time,
-                            },
-                        });
-                    }
+        let mut started = Vec::new();
+        let mut claimed_warm = Vec::new();
+        for (task_id, inst_id) in assigned_tasks {
+            if let Some(task) = self.tasks.get_mut(&task_id)
+                && let Some(instance) = self.instances.get_mut(&inst_id)
+                && instance.assign_task(task)
+            {
+                task.assigned_instance = Some(inst_id);
+                task.start_time = Some(self.current_time);
+
+                // Schedule completion event
+                let completion_time = self.current_time + task.remaining_time;
+                self.event_queue.push(TimedEvent {
+                    time: completion_time,
+                    event: Event::TaskCompletion {
+                        task_id,
+                        time: completion_time,
+                    },
+                });
+
+                started.push(task_id);
+                if let Some(ready_time) = self.warm_pool.remove(&inst_id).flatten() {
+                    claimed_warm.push((inst_id, ready_time));
                 }
             }
         }
+        for (instance_id, ready_time) in claimed_warm {
+            self.claim_warm_instance(instance_id, ready_time);
+        }
 
         // Third pass: launch instances for tasks that need them
         for task_id in tasks_needing_instances {
@@ -209,20 +353,44 @@ impl Simulator {
             }
         }
 
-        // Remove assigned tasks from pending queue
-        let assigned_ids: Vec<u64> = assigned_tasks.iter().map(|(id, _)| *id).collect();
-        self.pending_tasks.retain(|&id| !assigned_ids.contains(&id));
+        // Remove started tasks from pending queue; any whose assignment
+        // failed are retried on the next pass
+        self.pending_tasks.retain(|id| !started.contains(id));
     }
 
     /// Find an available instance that can fit the task
+    ///
+    /// Non-preemptible tasks only go to on-demand instances; other prioritized
+    /// tasks prefer on-demand capacity when it fits.
     fn find_available_instance(&self, task: &Task) -> Option<u64> {
-        for (id, instance) in &self.instances {
-            if instance.state == InstanceState::Running
-                && task.can_fit_in_memory(instance.available_memory_mb()) {
-                return Some(*id);
-            }
-        }
-        None
+        self.instances
+            .iter()
+            .filter(|(id, instance)| !self.warm_pool.contains_key(id) && Self::can_host(instance, task))
+            .min_by_key(|(_, instance)| task.priority > 0 && instance.instance_type == InstanceType::Spot)
+            .map(|(id, _)| *id)
+    }
+
+    /// Ready warm instance that can take a displaced task
+    ///
+    /// Same rules as `find_available_instance`, so a non-preemptible task
+    /// never fails over onto the (spot) warm pool.
+    fn find_warm_instance(&self, task: &Task) -> Option<u64> {
+        self.warm_pool
+            .iter()
+            .filter(|(_, ready)| ready.is_some())
+            .map(|(id, _)| *id)
+            .find(|id| {
+                self.instances
+                    .get(id)
+                    .is_some_and(|instance| Self::can_host(instance, task))
+            })
+    }
+
+    /// Whether `instance` is running and may take `task`
+    fn can_host(instance: &Instance, task: &Task) -> bool {
+        instance.state == InstanceState::Running
+            && (task.preemptible || instance.instance_type == InstanceType::OnDemand)
+            && task.can_fit_in_memory(instance.available_memory_mb())
     }
 
     /// Launch a new instance for a task
@@ -230,14 +398,28 @@ impl Simulator {
         let current_spot_price = self.get_spot_price_at(self.current_time);
 
         // Ask policy which instance type to use
-        let instance_type = self.policy.select_instance_type(
+        self.policy.observe_time(self.current_time);
+        let instance_type = match self.policy.select_instance_type(
             task,
             current_spot_price,
             self.on_demand_price,
-        );
+        ) {
+            _ if !task.preemptible => InstanceType::OnDemand,
+            instance_type => instance_type,
+        };
+
+        if task.preemption_count > 0 {
+            self.cold_failover_launches += 1;
+        }
 
+        let instance_id = self.launch_instance(instance_type);
+        self.awaiting_boot.insert(task.id, instance_id);
+    }
+
+    /// Create a `Booting` instance and schedule the event that brings it up
+    fn launch_instance(&mut self, instance_type: InstanceType) -> u64 {
         let hourly_cost = match instance_type {
-            InstanceType::Spot => current_spot_price,
+            InstanceType::Spot => self.get_spot_price_at(self.current_time),
             InstanceType::OnDemand => self.on_demand_price,
         };
 
@@ -245,22 +427,50 @@ impl Simulator {
         let instance_id = self.next_instance_id;
         self.next_instance_id += 1;
 
-        let instance = Instance::new(instance_id, instance_type, hourly_cost, self.current_time);
+        let mut instance = Instance::new(instance_id, instance_type, hourly_cost, self.current_time);
+        instance.state = InstanceState::Booting;
         self.instances.insert(instance_id, instance);
 
-        // Schedule instance launch event (immediate)
+        // Schedule instance launch event once booted and the model is loaded
+        let ready_time = self.current_time + self.boot_latency + self.model_load_latency;
         self.event_queue.push(TimedEvent {
-            time: self.current_time,
+            time: ready_time,
             event: Event::InstanceLaunch {
                 instance_id,
-                time: self.current_time,
+                time: ready_time,
                 instance_type,
             },
         });
 
-        // Schedule preemption for spot instances (simplified model)
-        if instance_type == InstanceType::Spot {
-            self.schedule_potential_preemption(instance_id);
+        instance_id
+    }
+
+    /// Launch spot instances until the policy's warm pool is full
+    fn replenish_warm_pool(&mut self) {
+        while self.warm_pool.len() < self.policy.warm_pool_size() {
+            let instance_id = self.launch_instance(InstanceType::Spot);
+            self.warm_pool.insert(instance_id, None);
+        }
+    }
+
+    /// Account for a warm instance that a displaced task was started on
+    ///
+    /// The instance has already left the pool; this bills its idle time,
+    /// records the failover and refills the pool.
+    fn claim_warm_instance(&mut self, instance_id: u64, ready_time: f64) {
+        self.bill_warm_idle(instance_id, ready_time, self.current_time);
+
+        self.warm_failovers += 1;
+        self.failover_latency_saved_hours += self.boot_latency + self.model_load_latency;
+        self.replenish_warm_pool();
+    }
+
+    /// Bill a warm instance for sitting idle between `from` and `to`
+    fn bill_warm_idle(&mut self, instance_id: u64, from: f64, to: f64) {
+        if let Some(instance) = self.instances.get(&instance_id) {
+            let cost = instance.hourly_cost * (to - from);
+            self.warm_pool_idle_cost += cost;
+            self.total_cost += cost;
         }
     }
 
@@ -272,7 +482,7 @@ impl Simulator {
 
         // Randomly determine if/when preemption occurs
         // For now: simple exponential distribution
-        let hours_until_preemption = -f64::ln(rand::random::<f64>()) / avg_preemption_rate;
+        let hours_until_preemption = -f64::ln(self.rng.r#gen::<f64>()) / avg_preemption_rate;
         let preemption_time = self.current_time + hours_until_preemption;
 
         self.event_queue.push(TimedEvent {
@@ -285,9 +495,23 @@ impl Simulator {
     }
 
     /// Handle instance launch
-    fn handle_instance_launch(&mut self, _instance_id: u64, _instance_type: InstanceType) {
-        // Instance already created in launch_instance_for_task
-        // This event is mainly for logging/metrics
+    fn handle_instance_launch(&mut self, instance_id: u64, instance_type: InstanceType) {
+        // Instance was created (Booting) in launch_instance_for_task
+        if let Some(instance) = self.instances.get_mut(&instance_id) {
+            instance.state = InstanceState::Running;
+
+            // Warmup is billed even though no task ran yet
+            self.total_cost += instance.hourly_cost * (self.current_time - instance.start_time);
+        }
+        self.awaiting_boot.retain(|_, booting| *booting != instance_id);
+        if let Some(ready_time) = self.warm_pool.get_mut(&instance_id) {
+            *ready_time = Some(self.current_time);
+        }
+
+        // Schedule preemption for spot instances (simplified model)
+        if instance_type == InstanceType::Spot {
+            self.schedule_potential_preemption(instance_id);
+        }
 
         // Try to assign pending tasks now that new instance is available
         self.assign_pending_tasks();
@@ -304,14 +528,14 @@ impl Simulator {
             task.completion_time = Some(self.current_time);
 
             // Release instance resources
-            if let Some(instance_id) = task.assigned_instance {
-                if let Some(instance) = self.instances.get_mut(&instance_id) {
-                    instance.release_task(task);
-
-                    // Update cost
-                    let runtime = self.current_time - task.start_time.unwrap_or(0.0);
-                    self.total_cost += instance.hourly_cost * runtime;
-                }
+            if let Some(instance_id) = task.assigned_instance
+                && let Some(instance) = self.instances.get_mut(&instance_id)
+            {
+                instance.release_task(task);
+
+                // Update cost
+                let runtime = self.current_time - task.start_time.unwrap_or(0.0);
+                self.total_cost += instance.hourly_cost * runtime;
             }
 
             // Mark as completed (only once)
@@ -330,6 +554,14 @@ impl Simulator {
                 return;
             }
 
+            // An idle warm instance has no tasks; bill it and refill the pool
+            if let Some(ready_time) = self.warm_pool.remove(&instance_id) {
+                if let Some(ready_time) = ready_time {
+                    self.bill_warm_idle(instance_id, ready_time, self.current_time);
+                }
+                self.replenish_warm_pool();
+            }
+
             // Find all tasks on this instance and reschedule them
             let affected_task_ids: Vec<u64> = self.tasks
                 .iter()
@@ -363,6 +595,7 @@ impl Simulator {
             if let Some(instance) = self.instances.get_mut(&instance_id) {
                 instance.state = InstanceState::Preempted;
                 instance.end_time = Some(self.current_time);
+                self.policy.observe_preemption(instance);
             }
 
             self.total_preemptions += 1;
@@ -386,76 +619,93 @@ impl Simulator {
     }
 
     /// Migrate tasks using configured migration strategy (optimal KM or naive greedy)
+    ///
+    /// Displaced tasks are planned one priority tier at a time, highest first,
+    /// so higher-priority tasks get the surviving capacity before lower ones.
     fn migrate_tasks_optimally(&mut self, displaced_task_ids: &[u64]) {
         if displaced_task_ids.is_empty() {
             return;
         }
 
-        // Collect displaced tasks
-        let displaced_tasks: Vec<Task> = displaced_task_ids
+        // Collect displaced tasks, highest priority first
+        let mut displaced_tasks: Vec<Task> = displaced_task_ids
             .iter()
             .filter_map(|id| self.tasks.get(id).cloned())
             .collect();
+        displaced_tasks.sort_by_key(|t| Reverse(t.priority));
 
-        // Collect available running instances
+        let mut assigned_task_ids = Vec::new();
+        for tier in displaced_tasks.chunk_by(|a, b| a.priority == b.priority) {
+            assigned_task_ids.extend(self.migrate_tier(tier));
+        }
+
+        // Tasks that couldn't be assigned optimally need new instances
+        for task in &displaced_tasks {
+            if !assigned_task_ids.contains(&task.id) {
+                // Add to pending queue for instance launch
+                self.pending_tasks.push(task.id);
+            }
+        }
+
+        // Launch instances for unassigned tasks
+        self.assign_pending_tasks();
+    }
+
+    /// Plan and apply migration for tasks of equal priority; returns the assigned task IDs
+    fn migrate_tier(&mut self, displaced_tasks: &[Task]) -> Vec<u64> {
+        // Collect available running instances (warm-pool instances are
+        // claimed individually in assign_pending_tasks)
         let available_instances: Vec<Instance> = self.instances
             .values()
-            .filter(|inst| inst.state == InstanceState::Running)
+            .filter(|inst| inst.state == InstanceState::Running && !self.warm_pool.contains_key(&inst.id))
             .cloned()
             .collect();
 
         // Choose migration strategy based on configuration
         let migration_plan = if self.use_optimal_migration {
             MigrationPlanner::plan_optimal_migration(
-                &displaced_tasks,
+                displaced_tasks,
                 &available_instances
             )
         } else {
             MigrationPlanner::plan_naive_migration(
-                &displaced_tasks,
+                displaced_tasks,
                 &available_instances
             )
         };
 
-        // Apply the migration plan
+        // Apply the migration plan in task order so seeded runs reproduce
+        let mut migration_plan: Vec<(u64, u64)> = migration_plan.into_iter().collect();
+        migration_plan.sort_unstable();
         let mut assigned_task_ids = Vec::new();
         for (task_id, instance_id) in migration_plan {
-            if let Some(task) = self.tasks.get_mut(&task_id) {
-                if let Some(instance) = self.instances.get_mut(&instance_id) {
-                    // Apply checkpoint recovery if available
-                    let time_saved = CheckpointPlanner::apply_checkpoint_recovery(task);
-                    self.total_time_saved_hours += time_saved;
-
-                    if instance.assign_task(task) {
-                        task.assigned_instance = Some(instance_id);
-                        task.start_time = Some(self.current_time);
-
-                        // Schedule completion event (accounting for checkpoint recovery)
-                        let completion_time = self.current_time + task.remaining_time;
-                        self.event_queue.push(TimedEvent {
+            if let Some(task) = self.tasks.get_mut(&task_id)
+                && let Some(instance) = self.instances.get_mut(&instance_id)
+            {
+                // Apply checkpoint recovery if available
+                let time_saved = CheckpointPlanner::apply_checkpoint_recovery(task);
+                self.total_time_saved_hours += time_saved;
+
+                if instance.assign_task(task) {
+                    task.assigned_instance = Some(instance_id);
+                    task.start_time = Some(self.current_time);
+
+                    // Schedule completion event (accounting for checkpoint recovery)
+                    let completion_time = self.current_time + task.remaining_time;
+                    self.event_queue.push(TimedEvent {
+                        time: completion_time,
+                        event: Event::TaskCompletion {
+                            task_id,
                             time: completion_time,
-                            event: Event::TaskCompletion {
-                                task_id,
-                                time: completion_time,
-                            },
-                        });
+                        },
+                    });
 
-                        assigned_task_ids.push(task_id);
-                    }
+                    assigned_task_ids.push(task_id);
                 }
             }
         }
 
-        // Tasks that couldn't be assigned optimally need new instances
-        for task_id in displaced_task_ids {
-            if !assigned_task_ids.contains(task_id) {
-                // Add to pending queue for instance launch
-                self.pending_tasks.push(*task_id);
-            }
-        }
-
-        // Launch instances for unassigned tasks
-        self.assign_pending_tasks();
+        assigned_task_ids
     }
 
     /// Get spot price at a specific time
@@ -475,9 +725,14 @@ impl Simulator {
     }
 
     /// Collect simulation results
-    fn collect_results(&self) -> SimulationResult {
+    fn collect_results(&self, end_time: f64) -> SimulationResult {
         let total_tasks = self.tasks.len();
         let completed_tasks = self.completed_tasks.len();
+        let deadline_misses = self
+            .tasks
+            .values()
+            .filter(|t| t.missed_deadline(end_time))
+            .count();
 
         // Calculate completion times
         let mut completion_times: Vec<f64> = self.completed_tasks
@@ -523,6 +778,12 @@ impl Simulator {
             checkpoints_attempted: self.checkpoints_attempted,
             checkpoints_successful: self.checkpoints_successful,
             total_time_saved_hours: self.total_time_saved_hours,
+            deadline_misses,
+            warm_pool_idle_cost: self.warm_pool_idle_cost,
+            warm_failovers: self.warm_failovers,
+            cold_failover_launches: self.cold_failover_launches,
+            failover_latency_saved_hours: self.failover_latency_saved_hours,
+            metrics: self.metrics.clone().map(MetricsRecorder::finish),
         }
     }
 }
@@ -530,7 +791,9 @@ impl Simulator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::policies::{GreedyPolicy, OnDemandOnlyPolicy};
+    use crate::policies::{
+        DeadlineAwarePolicy, GreedyPolicy, OnDemandOnlyPolicy, RateAwarePolicy, WarmPoolPolicy,
+    };
     use crate::spot_data::SpotPriceGenerator;
 
     #[test]
@@ -563,5 +826,239 @@ mod tests {
         assert_eq!(result.completed_tasks, 1);
         assert_eq!(result.total_preemptions, 0);
         assert!(result.total_cost > 0.0);  // Should have some cost
+        assert!(result.metrics.is_none());
+    }
+
+    #[test]
+    fn test_deadline_aware_places_tight_task_on_demand() {
+        let policy = Box::new(DeadlineAwarePolicy::new(2.0));
+        let spot_prices = SpotPriceGenerator::generate_simple(24.0, 0.30, 0.05);
+
+        let mut simulator = Simulator::new(policy, spot_prices, 1.00, true);
+        simulator.add_task(Task::new(1, 0.0, 4.0).with_deadline(5.0));
+
+        let result = simulator.run(24.0);
+
+        // On-demand placement: completes on time, never preempted
+        assert_eq!(result.completed_tasks, 1);
+        assert_eq!(result.total_preemptions, 0);
+        assert_eq!(result.deadline_misses, 0);
+        assert!((result.total_cost - 4.0).abs() < 1e-9, "4h at on-demand $1.00/hr");
+    }
+
+    #[test]
+    fn test_deadline_misses_counted() {
+        let policy = Box::new(OnDemandOnlyPolicy::new());
+        let spot_prices = SpotPriceGenerator::generate_simple(10.0, 0.30, 0.05);
+
+        let mut simulator = Simulator::new(policy, spot_prices, 1.00, true);
+        simulator.add_task(Task::new(1, 0.0, 4.0).with_deadline(3.0)); // Impossible
+        simulator.add_task(Task::new(2, 0.0, 2.0).with_deadline(3.0)); // Met
+
+        let result = simulator.run(10.0);
+        assert_eq!(result.deadline_misses, 1);
+    }
+
+    #[test]
+    fn test_metrics_cost_series_monotonic() {
+        let policy = Box::new(GreedyPolicy::new());
+        let spot_prices = SpotPriceGenerator::generate_simple(48.0, 0.30, 0.05);
+
+        let mut simulator = Simulator::new(policy, spot_prices, 1.00, true).with_metrics(0.5);
+        for i in 0..20 {
+            simulator.add_task(Task::new(i, i as f64, 2.0 + (i % 5) as f64));
+        }
+
+        let result = simulator.run(48.0);
+        let metrics = result.metrics.expect("metrics enabled");
+
+        // One sample every 30 minutes from 0 to 48 hours inclusive
+        assert_eq!(metrics.samples.len(), 97);
+
+        let costs = metrics.cost_series();
+        assert!(costs.windows(2).all(|w| w[1] >= w[0]), "cost must never decrease");
+        assert_eq!(*costs.last().unwrap(), result.total_cost);
+        assert!(metrics.samples.iter().any(|s| s.running_instances > 0));
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_results() {
+        let run = |seed: u64| {
+            let policy = Box::new(GreedyPolicy::new());
+            let spot_prices = SpotPriceGenerator::generate_simple(72.0, 0.30, 0.05);
+
+            let mut simulator = Simulator::new(policy, spot_prices, 1.00, true)
+                .with_rng(StdRng::seed_from_u64(seed));
+            for i in 0..30 {
+                simulator.add_task(Task::new(i, i as f64, 4.0 + (i % 7) as f64));
+            }
+            simulator.run(72.0)
+        };
+
+        let (a, b) = (run(7), run(7));
+        assert!(a.total_preemptions > 0, "scenario should exercise preemption sampling");
+        assert_eq!(a.total_cost, b.total_cost);
+        assert_eq!(a.completed_tasks, b.completed_tasks);
+        assert_eq!(a.total_preemptions, b.total_preemptions);
+        assert_eq!(a.average_completion_time, b.average_completion_time);
+    }
+
+    #[test]
+    fn test_tasks_wait_for_instance_boot() {
+        let policy = Box::new(OnDemandOnlyPolicy::new());
+        let spot_prices = SpotPriceGenerator::generate_simple(10.0, 0.30, 0.05);
+
+        let mut simulator =
+            Simulator::new(policy, spot_prices, 1.00, true).with_launch_latency(0.25, 0.25);
+        simulator.add_task(Task::new(1, 0.0, 1.0));
+        simulator.add_task(Task::new(2, 0.1, 1.0));
+
+        let result = simulator.run(10.0);
+
+        // Task 1 was still pending when task 2 arrived but doesn't get a
+        // second instance; nothing runs before the first instance is up at 0.5h
+        assert_eq!(simulator.instances.len(), 2);
+        assert_eq!(simulator.tasks[&1].start_time, Some(0.5));
+        assert_eq!(simulator.tasks[&2].start_time, Some(0.5));
+        assert_eq!(result.completed_tasks, 2);
+        // 2 x 0.5h warmup + 2 x 1h task at $1.00/hr
+        assert!((result.total_cost - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_warm_pool_avoids_cold_failover_launches() {
+        let run = |pool_size: usize| {
+            let policy = Box::new(WarmPoolPolicy::new(pool_size));
+            let spot_prices = SpotPriceGenerator::generate_simple(72.0, 0.30, 0.05);
+
+            let mut simulator = Simulator::new(policy, spot_prices, 1.00, true)
+                .with_launch_latency(0.1, 0.1)
+                .with_rng(StdRng::seed_from_u64(11));
+            for i in 0..30 {
+                simulator.add_task(Task::new(i, i as f64, 4.0 + (i % 7) as f64));
+            }
+            simulator.run(72.0)
+        };
+
+        let cold = run(0);
+        let warm = run(2);
+
+        assert!(cold.cold_failover_launches > 0, "scenario should exercise failover");
+        assert_eq!(cold.warm_failovers, 0);
+        assert_eq!(cold.warm_pool_idle_cost, 0.0);
+
+        assert!(warm.warm_failovers > 0);
+        assert!(warm.cold_failover_launches < cold.cold_failover_launches);
+        assert!(warm.warm_pool_idle_cost > 0.0);
+        assert!((warm.failover_latency_saved_hours - warm.warm_failovers as f64 * 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_warm_pool_respects_placement_rules() {
+        let policy = Box::new(WarmPoolPolicy::new(1));
+        let spot_prices = SpotPriceGenerator::generate_simple(10.0, 0.30, 0.05);
+        let mut simulator = Simulator::new(policy, spot_prices, 1.00, true);
+
+        let mut warm = Instance::new(0, InstanceType::Spot, 0.30, 0.0);
+        warm.state = InstanceState::Running;
+        simulator.instances.insert(0, warm);
+        simulator.warm_pool.insert(0, Some(0.0));
+        simulator.next_instance_id = 1;
+        simulator.current_time = 1.0;
+
+        // A displaced non-preemptible task must not land on warm spot capacity
+        let mut pinned = Task::new(1, 0.0, 2.0).non_preemptible();
+        pinned.preemption_count = 1;
+        simulator.tasks.insert(1, pinned);
+        simulator.pending_tasks.push(1);
+        simulator.assign_pending_tasks();
+
+        assert_eq!(simulator.tasks[&1].assigned_instance, None);
+        assert!(simulator.warm_pool.contains_key(&0));
+        assert_eq!(simulator.warm_failovers, 0);
+        assert_eq!(simulator.warm_pool_idle_cost, 0.0);
+        assert_eq!(simulator.instances[&1].instance_type, InstanceType::OnDemand);
+
+        // A displaced preemptible task claims it
+        let mut displaced = Task::new(2, 0.0, 2.0);
+        displaced.preemption_count = 1;
+        simulator.tasks.insert(2, displaced);
+        simulator.pending_tasks.push(2);
+        simulator.assign_pending_tasks();
+
+        assert_eq!(simulator.tasks[&2].assigned_instance, Some(0));
+        assert!(!simulator.pending_tasks.contains(&2));
+        assert!(!simulator.warm_pool.contains_key(&0));
+        assert_eq!(simulator.warm_failovers, 1);
+        assert!((simulator.warm_pool_idle_cost - 0.30).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_high_priority_task_migrated_first() {
+        let policy = Box::new(GreedyPolicy::new());
+        let spot_prices = SpotPriceGenerator::generate_simple(10.0, 0.30, 0.05);
+        let mut simulator = Simulator::new(policy, spot_prices, 1.00, true)
+            .with_rng(StdRng::seed_from_u64(1));
+
+        // Instance 0 runs both tasks; instance 1 only has room for one of them
+        let low = Task::new(1, 0.0, 5.0);
+        let high = Task::new(2, 0.0, 5.0).with_priority(5);
+        let mut doomed = Instance::new(0, InstanceType::Spot, 0.30, 0.0);
+        let mut survivor = Instance::new(1, InstanceType::Spot, 0.30, 0.0);
+        survivor.gpu_memory_used_mb = survivor.gpu_memory_gb * 1000.0 - 1500.0;
+        for mut task in [low, high] {
+            doomed.assign_task(&task);
+            task.assigned_instance = Some(0);
+            task.start_time = Some(0.0);
+            simulator.tasks.insert(task.id, task);
+        }
+        simulator.instances.insert(0, doomed);
+        simulator.instances.insert(1, survivor);
+        simulator.next_instance_id = 2;
+
+        simulator.current_time = 1.0;
+        simulator.handle_preemption(0);
+
+        // The high-priority task takes the free slot; the low-priority one
+        // waits for a newly launched instance
+        assert_eq!(simulator.tasks[&2].assigned_instance, Some(1));
+        assert_eq!(simulator.tasks[&1].assigned_instance, None);
+        assert_eq!(simulator.awaiting_boot.get(&1), Some(&2));
+    }
+
+    #[test]
+    fn test_policy_observes_idle_preemptions() {
+        let policy = Box::new(RateAwarePolicy::new(0.5, 1.0));
+        let spot_prices = SpotPriceGenerator::generate_simple(10.0, 0.30, 0.05);
+        let mut simulator = Simulator::new(policy, spot_prices, 1.00, true);
+
+        // A spot instance with no tasks on it
+        let idle = Instance::new(0, InstanceType::Spot, 0.30, 0.0);
+        simulator.instances.insert(0, idle);
+
+        simulator.current_time = 1.0;
+        simulator.handle_preemption(0);
+
+        // One preemption in the last hour is above the 0.5/h threshold
+        simulator.policy.observe_time(1.5);
+        let task = Task::new(1, 1.5, 2.0);
+        assert_eq!(
+            simulator.policy.select_instance_type(&task, 0.30, 1.00),
+            InstanceType::OnDemand
+        );
+    }
+
+    #[test]
+    fn test_non_preemptible_task_runs_on_demand() {
+        let policy = Box::new(GreedyPolicy::new());
+        let spot_prices = SpotPriceGenerator::generate_simple(10.0, 0.30, 0.05);
+
+        let mut simulator = Simulator::new(policy, spot_prices, 1.00, true);
+        simulator.add_task(Task::new(1, 0.0, 2.0).non_preemptible());
+
+        let result = simulator.run(10.0);
+        assert_eq!(result.completed_tasks, 1);
+        assert_eq!(result.total_preemptions, 0);
+        assert_eq!(simulator.instances[&0].instance_type, InstanceType::OnDemand);
     }
 }
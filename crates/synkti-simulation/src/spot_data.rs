@@ -4,11 +4,73 @@
 //! - Daily/weekly periodicity
 //! - Realistic preemption rates
 //! - Price volatility similar to AWS spot market
+//!
+//! Historical prices can also be replayed from AWS `describe-spot-price-history`
+//! exports (CSV or the CLI's native JSON) via [`SpotPriceGenerator::from_csv`]
+//! and [`SpotPriceGenerator::from_json`].
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
 
+use chrono::{DateTime, Utc};
+use rand::Rng;
 use rand_distr::{Distribution, Normal};
+use serde::Deserialize;
 
 use crate::types::SpotPrice;
 
+/// A single price change record from AWS spot price history
+#[derive(Debug, Clone)]
+struct PriceRecord {
+    timestamp: DateTime<Utc>,
+    instance_type: String,
+    availability_zone: String,
+    price: f64,
+}
+
+/// AWS CLI `describe-spot-price-history` JSON output
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SpotPriceHistoryResponse {
+    spot_price_history: Vec<SpotPriceHistoryEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SpotPriceHistoryEntry {
+    availability_zone: String,
+    instance_type: String,
+    spot_price: String,
+    timestamp: String,
+}
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+fn parse_timestamp(s: &str) -> io::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s.trim())
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| invalid_data(format!("Invalid timestamp '{}': {}", s.trim(), e)))
+}
+
+fn parse_price(s: &str) -> io::Result<f64> {
+    s.trim()
+        .parse::<f64>()
+        .map_err(|e| invalid_data(format!("Invalid price '{}': {}", s.trim(), e)))
+}
+
+/// Preemption probability for one sample interval at a given price
+///
+/// Preemption probability increases when price is low (high demand).
+/// Using inverse relationship: lower price → higher preemption risk
+fn preemption_probability(price: f64, on_demand_price: f64, base_preemption_rate: f64, dt: f64) -> f64 {
+    let price_ratio = price / on_demand_price;
+    let preemption_multiplier = (1.0 - price_ratio).max(0.1);
+    base_preemption_rate * preemption_multiplier * dt
+}
+
 /// Spot price generator using Ornstein-Uhlenbeck process
 pub struct SpotPriceGenerator {
     mean_price: f64,
@@ -46,9 +108,18 @@ impl SpotPriceGenerator {
     /// # Returns
     /// Vector of spot prices with preemption probabilities
     pub fn generate(&mut self, duration_hours: f64, sample_interval: f64) -> Vec<SpotPrice> {
+        self.generate_with_rng(duration_hours, sample_interval, &mut rand::thread_rng())
+    }
+
+    /// Generate spot price data using the given RNG (seeded runs are reproducible)
+    pub fn generate_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        duration_hours: f64,
+        sample_interval: f64,
+        rng: &mut R,
+    ) -> Vec<SpotPrice> {
         let num_samples = (duration_hours / sample_interval).ceil() as usize;
         let mut prices = Vec::with_capacity(num_samples);
-        let mut rng = rand::thread_rng();
         let normal = Normal::new(0.0, 1.0).unwrap();
 
         for i in 0..num_samples {
@@ -57,7 +128,7 @@ impl SpotPriceGenerator {
             // Ornstein-Uhlenbeck process: dX = θ(μ - X)dt + σdW
             // θ = mean reversion speed, μ = mean, σ = volatility
             let dt = sample_interval;
-            let dw = normal.sample(&mut rng) * dt.sqrt();
+            let dw = normal.sample(rng) * dt.sqrt();
 
             let mean_reversion = self.mean_reversion_speed * (self.mean_price - self.current_price);
             let diffusion = self.volatility * dw;
@@ -73,11 +144,12 @@ impl SpotPriceGenerator {
                 .max(self.on_demand_price * 0.1)
                 .min(self.on_demand_price * 0.95);
 
-            // Preemption probability increases when price is low (high demand)
-            // Using inverse relationship: lower price → higher preemption risk
-            let price_ratio = final_price / self.on_demand_price;
-            let preemption_multiplier = (1.0 - price_ratio).max(0.1);
-            let preemption_prob = self.base_preemption_rate * preemption_multiplier * dt;
+            let preemption_prob = preemption_probability(
+                final_price,
+                self.on_demand_price,
+                self.base_preemption_rate,
+                dt,
+            );
 
             prices.push(SpotPrice {
                 time,
@@ -91,7 +163,7 @@ impl SpotPriceGenerator {
 
     /// Generate a simple price trace (deterministic, for testing)
     pub fn generate_simple(duration_hours: f64, spot_price: f64, preemption_rate: f64) -> Vec<SpotPrice> {
-        let sample_interval = 1.0; // 1 hour
+        // 1 hour sample interval
         let num_samples = duration_hours as usize;
 
         (0..num_samples)
@@ -102,6 +174,188 @@ impl SpotPriceGenerator {
             })
             .collect()
     }
+
+    /// Load a price trace from an AWS spot price history CSV export
+    ///
+    /// Expects columns `Timestamp, InstanceType, AvailabilityZone, SpotPrice`.
+    /// A header row naming these columns may appear in any order (an optional
+    /// `ProductDescription` column is ignored); without a header the columns are
+    /// read positionally in the order above.
+    ///
+    /// # Arguments
+    /// * `path` - CSV file path
+    /// * `sample_interval` - Time between samples in hours (e.g., 0.1 = 6 minutes)
+    /// * `on_demand_price` - On-demand price used to derive preemption probability
+    /// * `base_preemption_rate` - Base preemption probability per hour
+    pub fn from_csv(
+        path: impl AsRef<Path>,
+        sample_interval: f64,
+        on_demand_price: f64,
+        base_preemption_rate: f64,
+    ) -> io::Result<Vec<SpotPrice>> {
+        let content = std::fs::read_to_string(path)?;
+        let records = Self::parse_csv(&content)?;
+        Self::resample(records, sample_interval, on_demand_price, base_preemption_rate)
+    }
+
+    /// Load a price trace from `aws ec2 describe-spot-price-history` JSON output
+    ///
+    /// Arguments are the same as [`SpotPriceGenerator::from_csv`].
+    pub fn from_json(
+        path: impl AsRef<Path>,
+        sample_interval: f64,
+        on_demand_price: f64,
+        base_preemption_rate: f64,
+    ) -> io::Result<Vec<SpotPrice>> {
+        let content = std::fs::read_to_string(path)?;
+        let records = Self::parse_json(&content)?;
+        Self::resample(records, sample_interval, on_demand_price, base_preemption_rate)
+    }
+
+    /// Parse CSV spot price history into raw price records
+    fn parse_csv(content: &str) -> io::Result<Vec<PriceRecord>> {
+        let mut lines = content
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .peekable();
+
+        // Column indices: timestamp, instance type, availability zone, price
+        let mut columns = (0, 1, 2, 3);
+
+        if let Some(first) = lines.peek() {
+            let header: Vec<String> = first
+                .split(',')
+                .map(|c| c.trim().trim_matches('"').to_ascii_lowercase())
+                .collect();
+
+            if header.iter().any(|c| c == "timestamp") {
+                let find = |name: &str| {
+                    header
+                        .iter()
+                        .position(|c| c == name)
+                        .ok_or_else(|| invalid_data(format!("CSV header missing '{}' column", name)))
+                };
+                columns = (
+                    find("timestamp")?,
+                    find("instancetype")?,
+                    find("availabilityzone")?,
+                    find("spotprice")?,
+                );
+                lines.next();
+            }
+        }
+
+        let (ts_col, type_col, az_col, price_col) = columns;
+        let min_fields = ts_col.max(type_col).max(az_col).max(price_col) + 1;
+
+        lines
+            .enumerate()
+            .map(|(i, line)| {
+                let fields: Vec<&str> = line.split(',').map(|f| f.trim().trim_matches('"')).collect();
+                if fields.len() < min_fields {
+                    return Err(invalid_data(format!(
+                        "CSV row {} has {} fields, expected at least {}",
+                        i + 1,
+                        fields.len(),
+                        min_fields
+                    )));
+                }
+
+                Ok(PriceRecord {
+                    timestamp: parse_timestamp(fields[ts_col])?,
+                    instance_type: fields[type_col].to_string(),
+                    availability_zone: fields[az_col].to_string(),
+                    price: parse_price(fields[price_col])?,
+                })
+            })
+            .collect()
+    }
+
+    /// Parse AWS CLI JSON spot price history into raw price records
+    fn parse_json(content: &str) -> io::Result<Vec<PriceRecord>> {
+        let response: SpotPriceHistoryResponse = serde_json::from_str(content)
+            .map_err(|e| invalid_data(format!("Invalid spot price history JSON: {}", e)))?;
+
+        response
+            .spot_price_history
+            .into_iter()
+            .map(|entry| {
+                Ok(PriceRecord {
+                    timestamp: parse_timestamp(&entry.timestamp)?,
+                    instance_type: entry.instance_type,
+                    availability_zone: entry.availability_zone,
+                    price: parse_price(&entry.spot_price)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Resample price change records onto a fixed interval
+    ///
+    /// AWS records are price *changes*: a price holds until the next record for
+    /// the same instance type and availability zone. When the history covers
+    /// several pools, each sample takes the cheapest currently-active price,
+    /// matching a scheduler that launches into the cheapest pool.
+    ///
+    /// Time is measured in hours from the earliest record.
+    fn resample(
+        mut records: Vec<PriceRecord>,
+        sample_interval: f64,
+        on_demand_price: f64,
+        base_preemption_rate: f64,
+    ) -> io::Result<Vec<SpotPrice>> {
+        if records.is_empty() {
+            return Err(invalid_data("Spot price history contains no records"));
+        }
+        if sample_interval <= 0.0 {
+            return Err(invalid_data("Sample interval must be positive"));
+        }
+
+        // AWS returns newest first; replay oldest first
+        records.sort_by_key(|r| r.timestamp);
+
+        let start = records[0].timestamp;
+        let end = records[records.len() - 1].timestamp;
+        let duration_hours = (end - start).num_seconds() as f64 / 3600.0;
+        let num_samples = (duration_hours / sample_interval).floor() as usize + 1;
+
+        let mut active: HashMap<(&str, &str), f64> = HashMap::new();
+        let mut next_record = 0;
+        let mut prices = Vec::with_capacity(num_samples);
+
+        for i in 0..num_samples {
+            let time = i as f64 * sample_interval;
+
+            while next_record < records.len() {
+                let record = &records[next_record];
+                let record_time = (record.timestamp - start).num_seconds() as f64 / 3600.0;
+                if record_time > time {
+                    break;
+                }
+                active.insert(
+                    (record.instance_type.as_str(), record.availability_zone.as_str()),
+                    record.price,
+                );
+                next_record += 1;
+            }
+
+            let price = active.values().copied().fold(f64::INFINITY, f64::min);
+
+            prices.push(SpotPrice {
+                time,
+                price,
+                preemption_probability: preemption_probability(
+                    price,
+                    on_demand_price,
+                    base_preemption_rate,
+                    sample_interval,
+                ),
+            });
+        }
+
+        Ok(prices)
+    }
 }
 
 #[cfg(test)]
@@ -131,4 +385,68 @@ mod tests {
             assert!(price.preemption_probability < 1.0);
         }
     }
+
+    fn fixture(name: &str) -> String {
+        format!("{}/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[test]
+    fn test_from_csv_resamples_history() {
+        let prices =
+            SpotPriceGenerator::from_csv(fixture("spot_price_history.csv"), 0.5, 1.00, 0.05).unwrap();
+
+        // 3 hours of history at 30-minute intervals: t = 0.0, 0.5, ..., 3.0
+        assert_eq!(prices.len(), 7);
+        assert_eq!(prices[0].time, 0.0);
+
+        // Cheapest active pool at each sample
+        assert_eq!(prices[0].price, 0.45); // only us-east-1a known
+        assert_eq!(prices[1].price, 0.40); // us-east-1b joins cheaper
+        assert_eq!(prices[2].price, 0.40);
+        assert_eq!(prices[4].price, 0.38); // us-east-1a drops at 2h
+        assert_eq!(prices[6].price, 0.38);
+
+        for price in &prices {
+            assert!(price.preemption_probability > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_from_json_matches_csv() {
+        let csv =
+            SpotPriceGenerator::from_csv(fixture("spot_price_history.csv"), 0.5, 1.00, 0.05).unwrap();
+        let json =
+            SpotPriceGenerator::from_json(fixture("spot_price_history.json"), 0.5, 1.00, 0.05).unwrap();
+
+        assert_eq!(csv.len(), json.len());
+        for (a, b) in csv.iter().zip(json.iter()) {
+            assert_eq!(a.time, b.time);
+            assert_eq!(a.price, b.price);
+        }
+    }
+
+    #[test]
+    fn test_csv_without_header() {
+        let records = SpotPriceGenerator::parse_csv(
+            "2024-01-17T10:00:00Z,g5.xlarge,us-east-1a,0.50\n\
+             2024-01-17T11:00:00Z,g5.xlarge,us-east-1a,0.60\n",
+        )
+        .unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].price, 0.60);
+        assert_eq!(records[1].availability_zone, "us-east-1a");
+    }
+
+    #[test]
+    fn test_csv_rejects_bad_price() {
+        let result = SpotPriceGenerator::parse_csv("2024-01-17T10:00:00Z,g5.xlarge,us-east-1a,cheap\n");
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_empty_history_is_error() {
+        let result = SpotPriceGenerator::resample(Vec::new(), 0.1, 1.00, 0.05);
+        assert!(result.is_err());
+    }
 }
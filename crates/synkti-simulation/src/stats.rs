@@ -0,0 +1,132 @@
+//! Multi-run aggregation
+//!
+//! Preemptions, arrivals and prices are random, so a single run says little
+//! about which policy is better. These helpers summarize repeated runs as a
+//! mean with a 95% confidence interval (Student's t).
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::simulator::SimulationResult;
+
+/// Two-sided 95% Student's t critical values for 1..=30 degrees of freedom
+const T_CRITICAL_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+    2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+    2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+
+/// Normal approximation used beyond the table
+const Z_95: f64 = 1.96;
+
+fn t_critical_95(degrees_of_freedom: usize) -> f64 {
+    match degrees_of_freedom {
+        0 => f64::NAN,
+        df if df <= T_CRITICAL_95.len() => T_CRITICAL_95[df - 1],
+        _ => Z_95,
+    }
+}
+
+/// Sample mean with a 95% confidence interval of `mean ± half_width`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub mean: f64,
+    /// Zero for a single sample (no spread to estimate)
+    pub half_width: f64,
+}
+
+impl ConfidenceInterval {
+    /// Compute the mean and 95% CI of `samples` (empty input yields NaN mean)
+    pub fn from_samples(samples: &[f64]) -> Self {
+        let n = samples.len();
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        if n < 2 {
+            return ConfidenceInterval { mean, half_width: 0.0 };
+        }
+
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        let std_error = (variance / n as f64).sqrt();
+
+        ConfidenceInterval {
+            mean,
+            half_width: t_critical_95(n - 1) * std_error,
+        }
+    }
+
+    pub fn lower(&self) -> f64 {
+        self.mean - self.half_width
+    }
+
+    pub fn upper(&self) -> f64 {
+        self.mean + self.half_width
+    }
+}
+
+impl fmt::Display for ConfidenceInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+        write!(f, "{:.*} ± {:.*}", precision, self.mean, precision, self.half_width)
+    }
+}
+
+/// Aggregate of one policy's results across seeded runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicySummary {
+    pub policy_name: String,
+    pub runs: usize,
+    pub cost: ConfidenceInterval,
+    /// Completed / total tasks, as a fraction
+    pub completion_rate: ConfidenceInterval,
+    pub preemptions: ConfidenceInterval,
+}
+
+impl PolicySummary {
+    /// Summarize runs of a single policy; `None` if `results` is empty
+    pub fn from_results(results: &[SimulationResult]) -> Option<Self> {
+        let first = results.first()?;
+        let collect = |f: fn(&SimulationResult) -> f64| -> Vec<f64> {
+            results.iter().map(f).collect()
+        };
+
+        Some(PolicySummary {
+            policy_name: first.policy_name.clone(),
+            runs: results.len(),
+            cost: ConfidenceInterval::from_samples(&collect(|r| r.total_cost)),
+            completion_rate: ConfidenceInterval::from_samples(&collect(|r| {
+                if r.total_tasks == 0 {
+                    0.0
+                } else {
+                    r.completed_tasks as f64 / r.total_tasks as f64
+                }
+            })),
+            preemptions: ConfidenceInterval::from_samples(&collect(|r| r.total_preemptions as f64)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confidence_interval_known_samples() {
+        let ci = ConfidenceInterval::from_samples(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+
+        // sd = 1.5811, se = 0.7071, t(4) = 2.776
+        assert!((ci.mean - 3.0).abs() < 1e-9);
+        assert!((ci.half_width - 1.963).abs() < 1e-3);
+        assert!(ci.lower() < 3.0 && ci.upper() > 3.0);
+        assert_eq!(format!("{}", ci), "3.00 ± 1.96");
+    }
+
+    #[test]
+    fn test_confidence_interval_degenerate() {
+        let single = ConfidenceInterval::from_samples(&[4.2]);
+        assert_eq!(single.mean, 4.2);
+        assert_eq!(single.half_width, 0.0);
+
+        let constant = ConfidenceInterval::from_samples(&[2.0; 50]);
+        assert_eq!(constant.half_width, 0.0);
+    }
+}
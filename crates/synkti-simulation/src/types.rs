@@ -12,6 +12,8 @@ pub enum InstanceType {
 /// State of an instance
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum InstanceState {
+    /// Launched but still booting / loading the model; not yet schedulable
+    Booting,
     Running,
     Preempted,
     Terminated,
@@ -107,6 +109,9 @@ pub struct Task {
     pub assigned_instance: Option<u64>,
     pub start_time: Option<f64>,
     pub completion_time: Option<f64>,
+    pub deadline: Option<f64>, // Absolute completion deadline (hours), if SLA-bound
+    pub priority: u8,          // Higher is more important; placed and migrated first
+    pub preemptible: bool,     // If false, the task only runs on on-demand instances
 
     // Inference-specific fields (for LLM tasks)
     pub tokens_total: u64,             // Total tokens to generate
@@ -139,6 +144,9 @@ impl Task {
             assigned_instance: None,
             start_time: None,
             completion_time: None,
+            deadline: None,
+            priority: 0,
+            preemptible: true,
 
             // Initialize inference fields
             tokens_total,
@@ -151,6 +159,45 @@ impl Task {
         }
     }
 
+    /// Set an absolute completion deadline (hours since simulation start)
+    pub fn with_deadline(mut self, deadline: f64) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Set the task's priority (higher values are placed and migrated first)
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Protect the task from preemption by keeping it off spot instances
+    pub fn non_preemptible(mut self) -> Self {
+        self.preemptible = false;
+        self
+    }
+
+    /// Slack before the deadline if the task ran uninterrupted from `current_time`
+    ///
+    /// Returns `None` for tasks without a deadline. Negative slack means the
+    /// deadline can no longer be met.
+    pub fn slack(&self, current_time: f64) -> Option<f64> {
+        self.deadline
+            .map(|deadline| deadline - (current_time + self.remaining_time))
+    }
+
+    /// Whether the task missed its deadline as of `current_time`
+    ///
+    /// A task misses if it completed after its deadline, or if it is still
+    /// incomplete once the deadline has passed.
+    pub fn missed_deadline(&self, current_time: f64) -> bool {
+        match (self.deadline, self.completion_time) {
+            (Some(deadline), Some(completed)) => completed > deadline,
+            (Some(deadline), None) => current_time >= deadline,
+            (None, _) => false,
+        }
+    }
+
     pub fn is_completed(&self) -> bool {
         self.completion_time.is_some()
     }
@@ -261,6 +308,21 @@ mod tests {
         assert_eq!(instance.gpu_memory_used_mb, 0.0);  // No memory allocated
     }
 
+    #[test]
+    fn test_task_deadline_slack() {
+        let mut task = Task::new(1, 0.0, 10.0).with_deadline(15.0);
+
+        assert_eq!(task.slack(0.0), Some(5.0));
+        assert_eq!(task.slack(6.0), Some(-1.0));
+        assert!(!task.missed_deadline(10.0));
+        assert!(task.missed_deadline(15.0));
+
+        task.completion_time = Some(12.0);
+        assert!(!task.missed_deadline(20.0));
+
+        assert_eq!(Task::new(2, 0.0, 10.0).slack(0.0), None);
+    }
+
     #[test]
     fn test_checkpoint_state() {
         let mut task = Task::new(1, 0.0, 10.0);